@@ -0,0 +1,1084 @@
+//! Arbitrary-precision unsigned integers, enough of them for the public-key
+//! sets: schoolbook multiplication, Knuth division and Montgomery modpow.
+use std::{
+    cmp::Ordering,
+    fmt,
+    ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Rem, Shl, Shr, Sub, SubAssign},
+    str::FromStr,
+};
+
+use eyre::{ensure, eyre, Result};
+use rand::Rng;
+
+use crate::set_one::challenge_one::decode_hex;
+
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    // Little endian 64-bit limbs with no zero limbs on top, so zero is empty
+    limbs: Vec<u64>,
+}
+
+impl BigUint {
+    pub fn zero() -> Self {
+        Self { limbs: Vec::new() }
+    }
+
+    pub fn one() -> Self {
+        Self::from(1u64)
+    }
+
+    fn from_limbs(limbs: Vec<u64>) -> Self {
+        let mut x = Self { limbs };
+        x.normalize();
+        x
+    }
+
+    fn normalize(&mut self) {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    pub fn is_one(&self) -> bool {
+        self.limbs == [1]
+    }
+
+    pub fn is_even(&self) -> bool {
+        !self.bit(0)
+    }
+
+    pub fn is_odd(&self) -> bool {
+        self.bit(0)
+    }
+
+    /// The value if it fits in a u64.
+    pub fn to_u64(&self) -> Option<u64> {
+        match self.limbs[..] {
+            [] => Some(0),
+            [x] => Some(x),
+            _ => None,
+        }
+    }
+
+    /// Number of bits needed to write this down; zero for zero.
+    pub fn bits(&self) -> usize {
+        self.limbs
+            .last()
+            .map(|top| self.limbs.len() * 64 - top.leading_zeros() as usize)
+            .unwrap_or(0)
+    }
+
+    /// Bit `i`, counting from the least significant.
+    pub fn bit(&self, i: usize) -> bool {
+        self.limbs.get(i / 64).is_some_and(|limb| (limb >> (i % 64)) & 1 == 1)
+    }
+
+    pub fn set_bit(&mut self, i: usize, value: bool) {
+        if i / 64 >= self.limbs.len() {
+            if !value {
+                return;
+            }
+            self.limbs.resize(i / 64 + 1, 0);
+        }
+
+        if value {
+            self.limbs[i / 64] |= 1 << (i % 64);
+        } else {
+            self.limbs[i / 64] &= !(1 << (i % 64));
+            self.normalize();
+        }
+    }
+
+    /// How many times 2 divides this. Zero for zero.
+    pub fn trailing_zeros(&self) -> usize {
+        self.limbs
+            .iter()
+            .position(|limb| *limb != 0)
+            .map(|i| i * 64 + self.limbs[i].trailing_zeros() as usize)
+            .unwrap_or(0)
+    }
+
+    pub fn from_bytes_be(bytes: &[u8]) -> Self {
+        let limbs = bytes
+            .rchunks(8)
+            .map(|chunk| chunk.iter().fold(0u64, |acc, b| (acc << 8) | *b as u64))
+            .collect();
+
+        Self::from_limbs(limbs)
+    }
+
+    pub fn from_bytes_le(bytes: &[u8]) -> Self {
+        let limbs = bytes
+            .chunks(8)
+            .map(|chunk| chunk.iter().rev().fold(0u64, |acc, b| (acc << 8) | *b as u64))
+            .collect();
+
+        Self::from_limbs(limbs)
+    }
+
+    /// Minimal big endian bytes. Zero is a single zero byte.
+    pub fn to_bytes_be(&self) -> Vec<u8> {
+        let mut bytes = self.to_bytes_le();
+        bytes.reverse();
+        bytes
+    }
+
+    /// Minimal little endian bytes. Zero is a single zero byte.
+    pub fn to_bytes_le(&self) -> Vec<u8> {
+        let mut bytes = self.limbs.iter().flat_map(|limb| limb.to_le_bytes()).collect::<Vec<u8>>();
+        while bytes.len() > 1 && bytes.last() == Some(&0) {
+            bytes.pop();
+        }
+        if bytes.is_empty() {
+            bytes.push(0);
+        }
+
+        bytes
+    }
+
+    /// Big endian, left padded with zeroes to exactly `len` bytes. Panics if
+    /// the value doesn't fit.
+    pub fn to_bytes_be_padded(&self, len: usize) -> Vec<u8> {
+        let bytes = self.to_bytes_be();
+        let bytes = if self.is_zero() { &[][..] } else { &bytes[..] };
+        assert!(bytes.len() <= len, "{} bytes don't fit in {len}", bytes.len());
+
+        let mut out = vec![0; len - bytes.len()];
+        out.extend(bytes);
+        out
+    }
+
+    /// Parses big endian hex like `decode_hex` does, except it also takes an
+    /// odd number of digits and ignores whitespace, so the multi-line primes
+    /// out of RFCs can be pasted straight in.
+    pub fn from_hex(hex: &[u8]) -> Result<Self> {
+        let mut digits = hex
+            .iter()
+            .copied()
+            .filter(|x| !x.is_ascii_whitespace())
+            .collect::<Vec<u8>>();
+        ensure!(!digits.is_empty(), "no hex digits");
+
+        if digits.len() % 2 == 1 {
+            digits.insert(0, b'0');
+        }
+
+        Ok(Self::from_bytes_be(&decode_hex(&digits)?))
+    }
+
+    /// Lowercase hex with no leading zeroes.
+    pub fn to_hex(&self) -> String {
+        format!("{self:x}")
+    }
+
+    /// Uniformly random below `bound`, which mustn't be zero.
+    pub fn random_below<R: Rng + ?Sized>(bound: &BigUint, rng: &mut R) -> Self {
+        assert!(!bound.is_zero(), "nothing is below zero");
+
+        // Rejection sampling wastes at most half the draws
+        loop {
+            let candidate = Self::random_bits(bound.bits(), rng);
+            if &candidate < bound {
+                return candidate;
+            }
+        }
+    }
+
+    /// Uniformly random with at most `bits` bits.
+    pub fn random_bits<R: Rng + ?Sized>(bits: usize, rng: &mut R) -> Self {
+        let mut limbs = (0..bits.div_ceil(64)).map(|_| rng.gen::<u64>()).collect::<Vec<u64>>();
+        if !bits.is_multiple_of(64) {
+            if let Some(top) = limbs.last_mut() {
+                *top >>= 64 - bits % 64;
+            }
+        }
+
+        Self::from_limbs(limbs)
+    }
+
+    pub fn pow(&self, mut exp: u32) -> Self {
+        let mut base = self.clone();
+        let mut out = Self::one();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                out = &out * &base;
+            }
+            base = &base * &base;
+            exp >>= 1;
+        }
+
+        out
+    }
+
+    /// The largest r with r^k <= self.
+    pub fn nth_root(&self, k: u32) -> BigUint {
+        assert!(k > 0, "no such thing as a 0th root");
+        if self.is_zero() || k == 1 {
+            return self.clone();
+        }
+
+        // Newton's method from above: start at a power of two that's at
+        // least the root and step down until it stops going down
+        let mut x = Self::one() << self.bits().div_ceil(k as usize);
+        loop {
+            let next = (&x * (k - 1) as u64 + self / &x.pow(k - 1)) / k as u64;
+            if next >= x {
+                return x;
+            }
+            x = next;
+        }
+    }
+
+    /// The r with r^k = self exactly, if there is one.
+    pub fn exact_nth_root(&self, k: u32) -> Option<BigUint> {
+        let root = self.nth_root(k);
+        (&root.pow(k) == self).then_some(root)
+    }
+
+    /// Quotient and remainder. Panics on division by zero.
+    pub fn divmod(&self, divisor: &BigUint) -> (BigUint, BigUint) {
+        assert!(!divisor.is_zero(), "division by zero");
+
+        if self < divisor {
+            return (Self::zero(), self.clone());
+        }
+
+        if let [d] = divisor.limbs[..] {
+            let (q, r) = divmod_limb(&self.limbs, d);
+            return (Self::from_limbs(q), Self::from(r));
+        }
+
+        let (q, r) = divmod_knuth(&self.limbs, &divisor.limbs);
+        (Self::from_limbs(q), Self::from_limbs(r))
+    }
+
+    /// self^exp mod modulus. Odd moduli, which is all of them in practice,
+    /// go through Montgomery multiplication.
+    pub fn modpow(&self, exp: &BigUint, modulus: &BigUint) -> BigUint {
+        assert!(!modulus.is_zero(), "modulus is zero");
+
+        if modulus.is_one() {
+            return Self::zero();
+        }
+
+        if modulus.is_odd() {
+            return Montgomery::new(modulus).pow(self, exp);
+        }
+
+        // Square and multiply, the slow way
+        let mut base = self % modulus;
+        let mut out = Self::one();
+        for i in 0..exp.bits() {
+            if exp.bit(i) {
+                out = &(&out * &base) % modulus;
+            }
+            base = &(&base * &base) % modulus;
+        }
+
+        out
+    }
+
+    /// The x with self * x = 1 mod modulus, if there is one.
+    pub fn modinv(&self, modulus: &BigUint) -> Option<BigUint> {
+        if modulus.is_one() {
+            return Some(Self::zero());
+        }
+
+        // Extended Euclid, keeping only the coefficient of `self` and keeping
+        // it reduced mod `modulus` so it never goes negative.
+        let (mut old_r, mut r) = (self % modulus, modulus.clone());
+        let (mut old_s, mut s) = (Self::one(), Self::zero());
+        while !r.is_zero() {
+            let (q, rem) = old_r.divmod(&r);
+            old_r = std::mem::replace(&mut r, rem);
+
+            let qs = &(&q * &s) % modulus;
+            let next = &(&old_s + modulus) - &qs;
+            old_s = std::mem::replace(&mut s, &next % modulus);
+        }
+
+        old_r.is_one().then_some(old_s)
+    }
+
+    pub fn gcd(&self, other: &BigUint) -> BigUint {
+        let (mut a, mut b) = (self.clone(), other.clone());
+        while !b.is_zero() {
+            let r = &a % &b;
+            a = std::mem::replace(&mut b, r);
+        }
+
+        a
+    }
+
+    /// self - other, or None if that would go negative.
+    pub fn checked_sub(&self, other: &BigUint) -> Option<BigUint> {
+        if self < other {
+            return None;
+        }
+
+        let mut limbs = self.limbs.clone();
+        sub_assign(&mut limbs, &other.limbs);
+        Some(Self::from_limbs(limbs))
+    }
+}
+
+// a += b, growing a as needed
+fn add_assign(a: &mut Vec<u64>, b: &[u64]) {
+    if a.len() < b.len() {
+        a.resize(b.len(), 0);
+    }
+
+    let mut carry = false;
+    for (i, x) in a.iter_mut().enumerate() {
+        let y = b.get(i).copied().unwrap_or(0);
+        if y == 0 && !carry && i >= b.len() {
+            break;
+        }
+
+        let (sum, c1) = x.overflowing_add(y);
+        let (sum, c2) = sum.overflowing_add(carry as u64);
+        *x = sum;
+        carry = c1 || c2;
+    }
+
+    if carry {
+        a.push(1);
+    }
+}
+
+// a -= b, returning whether it borrowed off the top
+fn sub_assign(a: &mut [u64], b: &[u64]) -> bool {
+    let mut borrow = false;
+    for (i, x) in a.iter_mut().enumerate() {
+        let y = b.get(i).copied().unwrap_or(0);
+        if y == 0 && !borrow && i >= b.len() {
+            break;
+        }
+
+        let (diff, b1) = x.overflowing_sub(y);
+        let (diff, b2) = diff.overflowing_sub(borrow as u64);
+        *x = diff;
+        borrow = b1 || b2;
+    }
+
+    borrow
+}
+
+fn mul_limbs(a: &[u64], b: &[u64]) -> Vec<u64> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+
+    let mut out = vec![0u64; a.len() + b.len()];
+    for (i, x) in a.iter().enumerate() {
+        let mut carry = 0u128;
+        for (j, y) in b.iter().enumerate() {
+            let t = out[i + j] as u128 + *x as u128 * *y as u128 + carry;
+            out[i + j] = t as u64;
+            carry = t >> 64;
+        }
+        out[i + b.len()] = carry as u64;
+    }
+
+    out
+}
+
+fn divmod_limb(a: &[u64], d: u64) -> (Vec<u64>, u64) {
+    let mut q = vec![0u64; a.len()];
+    let mut r = 0u128;
+    for (i, x) in a.iter().enumerate().rev() {
+        let cur = (r << 64) | *x as u128;
+        q[i] = (cur / d as u128) as u64;
+        r = cur % d as u128;
+    }
+
+    (q, r as u64)
+}
+
+// Knuth's algorithm D (TAOCP 4.3.1) for a divisor of at least two limbs
+// that's no bigger than the dividend.
+fn divmod_knuth(a: &[u64], d: &[u64]) -> (Vec<u64>, Vec<u64>) {
+    // Normalise so the divisor's top bit is set, which keeps the quotient
+    // digit estimates off by at most two
+    let shift = d.last().unwrap().leading_zeros() as usize;
+    let v = shl_limbs(d, shift);
+    let v = &v[..d.len()];
+    let mut u = shl_limbs(a, shift);
+    u.resize(a.len() + 1, 0);
+
+    let n = v.len();
+    let m = a.len() - n;
+    let mut q = vec![0u64; m + 1];
+    let base = 1u128 << 64;
+
+    for j in (0..=m).rev() {
+        let top = ((u[j + n] as u128) << 64) | u[j + n - 1] as u128;
+        let mut qhat = top / v[n - 1] as u128;
+        let mut rhat = top % v[n - 1] as u128;
+        while qhat >= base || qhat * v[n - 2] as u128 > ((rhat << 64) | u[j + n - 2] as u128) {
+            qhat -= 1;
+            rhat += v[n - 1] as u128;
+            if rhat >= base {
+                break;
+            }
+        }
+
+        // u[j..=j+n] -= qhat * v
+        let mut carry = 0u128;
+        let mut borrow = false;
+        for i in 0..n {
+            let p = qhat * v[i] as u128 + carry;
+            carry = p >> 64;
+            let (diff, b1) = u[i + j].overflowing_sub(p as u64);
+            let (diff, b2) = diff.overflowing_sub(borrow as u64);
+            u[i + j] = diff;
+            borrow = b1 || b2;
+        }
+        let (diff, b1) = u[j + n].overflowing_sub(carry as u64);
+        let (diff, b2) = diff.overflowing_sub(borrow as u64);
+        u[j + n] = diff;
+
+        // Went one too far, so add a v back
+        if b1 || b2 {
+            qhat -= 1;
+            let mut carry = false;
+            for i in 0..n {
+                let (sum, c1) = u[i + j].overflowing_add(v[i]);
+                let (sum, c2) = sum.overflowing_add(carry as u64);
+                u[i + j] = sum;
+                carry = c1 || c2;
+            }
+            u[j + n] = u[j + n].wrapping_add(carry as u64);
+        }
+
+        q[j] = qhat as u64;
+    }
+
+    u.truncate(n);
+    (q, shr_limbs(&u, shift))
+}
+
+fn shl_limbs(a: &[u64], shift: usize) -> Vec<u64> {
+    let (limbs, bits) = (shift / 64, shift % 64);
+    let mut out = vec![0u64; limbs];
+    if bits == 0 {
+        out.extend(a);
+        return out;
+    }
+
+    let mut carry = 0;
+    for x in a {
+        out.push((x << bits) | carry);
+        carry = x >> (64 - bits);
+    }
+    out.push(carry);
+
+    out
+}
+
+fn shr_limbs(a: &[u64], shift: usize) -> Vec<u64> {
+    let (limbs, bits) = (shift / 64, shift % 64);
+    if limbs >= a.len() {
+        return Vec::new();
+    }
+
+    let a = &a[limbs..];
+    if bits == 0 {
+        return a.to_vec();
+    }
+
+    (0..a.len())
+        .map(|i| (a[i] >> bits) | a.get(i + 1).map(|x| x << (64 - bits)).unwrap_or(0))
+        .collect()
+}
+
+// Arithmetic mod an odd modulus with everything kept multiplied by
+// R = 2^(64 * limbs), so reductions are shifts rather than divisions.
+struct Montgomery<'a> {
+    modulus: &'a BigUint,
+    // -modulus^-1 mod 2^64
+    inv: u64,
+    // R^2 mod modulus, for getting into Montgomery form
+    r2: BigUint,
+}
+
+impl<'a> Montgomery<'a> {
+    fn new(modulus: &'a BigUint) -> Self {
+        let n = modulus.limbs.len();
+
+        // Newton's iteration doubles the correct low bits each time
+        let m0 = modulus.limbs[0];
+        let mut inv = 1u64;
+        for _ in 0..6 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(m0.wrapping_mul(inv)));
+        }
+
+        let r2 = &(BigUint::one() << (128 * n)) % modulus;
+        Self { modulus, inv: inv.wrapping_neg(), r2 }
+    }
+
+    // a * b / R mod modulus, with both inputs already reduced
+    fn mul(&self, a: &[u64], b: &[u64]) -> Vec<u64> {
+        let m = &self.modulus.limbs;
+        let n = m.len();
+        let mut t = vec![0u64; n + 2];
+
+        for i in 0..n {
+            let bi = b.get(i).copied().unwrap_or(0) as u128;
+            let mut carry = 0u128;
+            for (j, tj) in t[..n].iter_mut().enumerate() {
+                let s = *tj as u128 + a.get(j).copied().unwrap_or(0) as u128 * bi + carry;
+                *tj = s as u64;
+                carry = s >> 64;
+            }
+            let s = t[n] as u128 + carry;
+            t[n] = s as u64;
+            t[n + 1] = (s >> 64) as u64;
+
+            // Add the multiple of the modulus that clears the bottom limb,
+            // then shift it away
+            let mu = t[0].wrapping_mul(self.inv) as u128;
+            let mut carry = (t[0] as u128 + mu * m[0] as u128) >> 64;
+            for j in 1..n {
+                let s = t[j] as u128 + mu * m[j] as u128 + carry;
+                t[j - 1] = s as u64;
+                carry = s >> 64;
+            }
+            let s = t[n] as u128 + carry;
+            t[n - 1] = s as u64;
+            t[n] = t[n + 1] + (s >> 64) as u64;
+            t[n + 1] = 0;
+        }
+
+        t.truncate(n + 1);
+        if !lt_limbs(&t, m) {
+            sub_assign(&mut t, m);
+        }
+        t.truncate(n);
+
+        t
+    }
+
+    fn pow(&self, base: &BigUint, exp: &BigUint) -> BigUint {
+        let base = base % self.modulus;
+        let one = self.mul(&BigUint::one().limbs, &self.r2.limbs);
+
+        // base^0 through base^15, for eating the exponent four bits at a time
+        let mut table = vec![one.clone(), self.mul(&base.limbs, &self.r2.limbs)];
+        for i in 2..16 {
+            table.push(self.mul(&table[i - 1], &table[1]));
+        }
+
+        let mut out = one;
+        for window in (0..exp.bits().div_ceil(4)).rev() {
+            for _ in 0..4 {
+                out = self.mul(&out, &out);
+            }
+
+            let digit = (0..4).fold(0, |acc, i| acc | (exp.bit(window * 4 + i) as usize) << i);
+            if digit != 0 {
+                out = self.mul(&out, &table[digit]);
+            }
+        }
+
+        BigUint::from_limbs(self.mul(&out, &[1]))
+    }
+}
+
+// a < b, ignoring zero limbs on top
+fn lt_limbs(a: &[u64], b: &[u64]) -> bool {
+    let len = a.len().max(b.len());
+    for i in (0..len).rev() {
+        let (x, y) = (a.get(i).copied().unwrap_or(0), b.get(i).copied().unwrap_or(0));
+        if x != y {
+            return x < y;
+        }
+    }
+
+    false
+}
+
+impl From<u64> for BigUint {
+    fn from(x: u64) -> Self {
+        Self::from_limbs(vec![x])
+    }
+}
+
+impl From<u32> for BigUint {
+    fn from(x: u32) -> Self {
+        Self::from(x as u64)
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl FromStr for BigUint {
+    type Err = eyre::Error;
+
+    /// Decimal.
+    fn from_str(s: &str) -> Result<Self> {
+        ensure!(!s.is_empty(), "no digits");
+
+        s.bytes().try_fold(Self::zero(), |acc, digit| {
+            let digit = (digit as char)
+                .to_digit(10)
+                .ok_or_else(|| eyre!("{:?} isn't a decimal digit", digit as char))?;
+
+            Ok(&(&acc * 10) + digit as u64)
+        })
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_zero() {
+            return f.pad_integral(true, "", "0");
+        }
+
+        // Peel off 19 decimal digits at a time, the most that fit in a limb
+        const CHUNK: u64 = 10_000_000_000_000_000_000;
+        let mut chunks = Vec::new();
+        let mut rest = self.limbs.clone();
+        while !rest.is_empty() {
+            let (q, r) = divmod_limb(&rest, CHUNK);
+            chunks.push(r);
+            rest = BigUint::from_limbs(q).limbs;
+        }
+
+        let mut digits = chunks.pop().unwrap().to_string();
+        for chunk in chunks.iter().rev() {
+            digits.push_str(&format!("{chunk:019}"));
+        }
+
+        f.pad_integral(true, "", &digits)
+    }
+}
+
+impl fmt::LowerHex for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut digits = format!("{:x}", self.limbs.last().copied().unwrap_or(0));
+        for limb in self.limbs.iter().rev().skip(1) {
+            digits.push_str(&format!("{limb:016x}"));
+        }
+
+        f.pad_integral(true, "0x", &digits)
+    }
+}
+
+impl fmt::Debug for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self:#x}")
+    }
+}
+
+impl Add<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, rhs: &BigUint) -> BigUint {
+        let mut limbs = self.limbs.clone();
+        add_assign(&mut limbs, &rhs.limbs);
+        BigUint::from_limbs(limbs)
+    }
+}
+
+impl Sub<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    /// Panics if the result would be negative.
+    fn sub(self, rhs: &BigUint) -> BigUint {
+        self.checked_sub(rhs).expect("BigUint subtraction underflowed")
+    }
+}
+
+impl Mul<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, rhs: &BigUint) -> BigUint {
+        BigUint::from_limbs(mul_limbs(&self.limbs, &rhs.limbs))
+    }
+}
+
+impl Div<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn div(self, rhs: &BigUint) -> BigUint {
+        self.divmod(rhs).0
+    }
+}
+
+impl Rem<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn rem(self, rhs: &BigUint) -> BigUint {
+        self.divmod(rhs).1
+    }
+}
+
+// The by-reference impls above do the work. These fill in the owned and u64
+// combinations so callers don't have to sprinkle & and BigUint::from about.
+macro_rules! forward_binop {
+    ($($trait:ident $method:ident),*) => {$(
+        impl $trait<BigUint> for BigUint {
+            type Output = BigUint;
+
+            fn $method(self, rhs: BigUint) -> BigUint {
+                (&self).$method(&rhs)
+            }
+        }
+
+        impl $trait<&BigUint> for BigUint {
+            type Output = BigUint;
+
+            fn $method(self, rhs: &BigUint) -> BigUint {
+                (&self).$method(rhs)
+            }
+        }
+
+        impl $trait<BigUint> for &BigUint {
+            type Output = BigUint;
+
+            fn $method(self, rhs: BigUint) -> BigUint {
+                self.$method(&rhs)
+            }
+        }
+
+        impl $trait<u64> for &BigUint {
+            type Output = BigUint;
+
+            fn $method(self, rhs: u64) -> BigUint {
+                self.$method(&BigUint::from(rhs))
+            }
+        }
+
+        impl $trait<u64> for BigUint {
+            type Output = BigUint;
+
+            fn $method(self, rhs: u64) -> BigUint {
+                (&self).$method(&BigUint::from(rhs))
+            }
+        }
+    )*};
+}
+
+forward_binop!(Add add, Sub sub, Mul mul, Div div, Rem rem);
+
+impl AddAssign<&BigUint> for BigUint {
+    fn add_assign(&mut self, rhs: &BigUint) {
+        add_assign(&mut self.limbs, &rhs.limbs);
+    }
+}
+
+impl SubAssign<&BigUint> for BigUint {
+    fn sub_assign(&mut self, rhs: &BigUint) {
+        *self = &*self - rhs;
+    }
+}
+
+impl MulAssign<&BigUint> for BigUint {
+    fn mul_assign(&mut self, rhs: &BigUint) {
+        *self = &*self * rhs;
+    }
+}
+
+impl Shl<usize> for &BigUint {
+    type Output = BigUint;
+
+    fn shl(self, shift: usize) -> BigUint {
+        BigUint::from_limbs(shl_limbs(&self.limbs, shift))
+    }
+}
+
+impl Shl<usize> for BigUint {
+    type Output = BigUint;
+
+    fn shl(self, shift: usize) -> BigUint {
+        &self << shift
+    }
+}
+
+impl Shr<usize> for &BigUint {
+    type Output = BigUint;
+
+    fn shr(self, shift: usize) -> BigUint {
+        BigUint::from_limbs(shr_limbs(&self.limbs, shift))
+    }
+}
+
+impl Shr<usize> for BigUint {
+    type Output = BigUint;
+
+    fn shr(self, shift: usize) -> BigUint {
+        &self >> shift
+    }
+}
+
+/// A signed wrapper around `BigUint`, for the few places (like Bézout
+/// coefficients) where things go negative.
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    negative: bool,
+    magnitude: BigUint,
+}
+
+impl BigInt {
+    pub fn new(negative: bool, magnitude: BigUint) -> Self {
+        // No negative zero
+        Self { negative: negative && !magnitude.is_zero(), magnitude }
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn magnitude(&self) -> &BigUint {
+        &self.magnitude
+    }
+
+    /// The representative of this mod `modulus` in [0, modulus).
+    pub fn rem_euclid(&self, modulus: &BigUint) -> BigUint {
+        let r = &self.magnitude % modulus;
+        if self.negative && !r.is_zero() {
+            modulus - &r
+        } else {
+            r
+        }
+    }
+}
+
+impl From<BigUint> for BigInt {
+    fn from(magnitude: BigUint) -> Self {
+        Self::new(false, magnitude)
+    }
+}
+
+impl fmt::Debug for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{:?}", if self.negative { "-" } else { "" }, self.magnitude)
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(!self.negative, "", &self.magnitude.to_string())
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::new(!self.negative, self.magnitude.clone())
+    }
+}
+
+impl Add<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, rhs: &BigInt) -> BigInt {
+        if self.negative == rhs.negative {
+            return BigInt::new(self.negative, &self.magnitude + &rhs.magnitude);
+        }
+
+        // Opposite signs: the bigger magnitude wins
+        match self.magnitude.cmp(&rhs.magnitude) {
+            Ordering::Less => BigInt::new(rhs.negative, &rhs.magnitude - &self.magnitude),
+            _ => BigInt::new(self.negative, &self.magnitude - &rhs.magnitude),
+        }
+    }
+}
+
+impl Sub<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, rhs: &BigInt) -> BigInt {
+        self + &-rhs
+    }
+}
+
+impl Mul<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, rhs: &BigInt) -> BigInt {
+        BigInt::new(self.negative != rhs.negative, &self.magnitude * &rhs.magnitude)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(x: &str) -> BigUint {
+        BigUint::from_hex(x.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn conversions() {
+        let x = hex("0102030405060708090a0b0c0d0e0f10");
+
+        assert_eq!(x.to_bytes_be(), (1..=16).collect::<Vec<u8>>());
+        assert_eq!(x.to_bytes_le(), (1..=16).rev().collect::<Vec<u8>>());
+        assert_eq!(BigUint::from_bytes_le(&x.to_bytes_le()), x);
+        assert_eq!(x.to_hex(), "102030405060708090a0b0c0d0e0f10");
+        assert_eq!(x.bits(), 121);
+        assert_eq!(x.to_bytes_be_padded(18)[..3], [0, 0, 1]);
+
+        assert_eq!(hex("FFF"), BigUint::from(0xFFFu64));
+        assert_eq!(hex("00 00\n01"), BigUint::one());
+        assert!(BigUint::from_hex(b"12G4").is_err());
+
+        assert_eq!(BigUint::zero().to_bytes_be(), [0]);
+        assert_eq!(BigUint::zero().to_hex(), "0");
+        assert_eq!(BigUint::zero().to_bytes_be_padded(2), [0, 0]);
+
+        let big = "123456789012345678901234567890123456789012345678901234567890";
+        assert_eq!(big.parse::<BigUint>().unwrap().to_string(), big);
+        assert_eq!(BigUint::zero().to_string(), "0");
+        assert!("12a".parse::<BigUint>().is_err());
+    }
+
+    #[test]
+    fn bits() {
+        let mut x = BigUint::zero();
+        x.set_bit(130, true);
+        x.set_bit(3, true);
+
+        assert!(x.bit(130) && x.bit(3) && !x.bit(4));
+        assert_eq!(x.trailing_zeros(), 3);
+        assert_eq!(x, (BigUint::one() << 130) + 8);
+
+        x.set_bit(130, false);
+        assert_eq!(x, BigUint::from(8u64));
+        assert_eq!(x.bits(), 4);
+    }
+
+    #[test]
+    fn arithmetic() {
+        let a = hex("fedcba9876543210fedcba9876543210fedcba9876543210");
+        let b = hex("123456789abcdef0123456789");
+
+        assert_eq!(&a + &b, hex("fedcba9876543210fedcba999999999aaaaaa99999999999"));
+        assert_eq!(&a - &b, hex("fedcba9876543210fedcba97530eca87530ecb97530eca87"));
+        assert_eq!(&a * &b, hex("121fa00ad77d742247acc913fa630fef15c4fcbcfa630fef03a55cb222e59bccce1833a90"));
+        assert_eq!(&a / &b, hex("e0000000000000d2f0000000"));
+        assert_eq!(&a % &b, hex("96551310fedcbb2606543210"));
+        assert_eq!(a.pow(3), &(&a * &a) * &a);
+        assert_eq!(&a >> 100, hex("fedcba9876543210fedcba9"));
+        assert_eq!((&a << 100) >> 100, a);
+        assert!(b.checked_sub(&a).is_none());
+    }
+
+    #[test]
+    fn division() {
+        let mut rng = rand::thread_rng();
+
+        // Random sizes hit the single-limb path, the add-back step and
+        // everything in between
+        for _ in 0..500 {
+            let a = BigUint::random_bits(rng.gen_range(0..600), &mut rng);
+            let b = BigUint::random_bits(rng.gen_range(1..400), &mut rng) + 1;
+            let (q, r) = a.divmod(&b);
+
+            assert!(r < b);
+            assert_eq!(&q * &b + &r, a);
+        }
+    }
+
+    #[test]
+    fn modular() {
+        // 2^127 - 1 is prime, so Fermat says a^(p-1) = 1
+        let p = (BigUint::one() << 127) - 1;
+        let a = hex("deadbeefcafebabe1234");
+        assert!(a.modpow(&(&p - 1), &p).is_one());
+        assert_eq!(a.modpow(&BigUint::zero(), &p), BigUint::one());
+
+        // Even modulus takes the slow path
+        assert_eq!(BigUint::from(3u64).modpow(&BigUint::from(200u64), &BigUint::from(1000u64)), BigUint::from(1u64));
+
+        let inv = a.modinv(&p).unwrap();
+        assert!((&a * &inv % &p).is_one());
+        assert!(BigUint::from(6u64).modinv(&BigUint::from(9u64)).is_none());
+        assert_eq!(BigUint::from(3u64).modinv(&BigUint::from(10u64)), Some(BigUint::from(7u64)));
+
+        assert_eq!(BigUint::from(84u64).gcd(&BigUint::from(36u64)), BigUint::from(12u64));
+    }
+
+    #[test]
+    fn modpow_matches_naive() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..50 {
+            let m = BigUint::random_bits(rng.gen_range(2..300), &mut rng) + 1;
+            let base = BigUint::random_bits(320, &mut rng);
+            let exp = BigUint::random_bits(rng.gen_range(0..40), &mut rng);
+
+            let mut expected = BigUint::one() % &m;
+            for _ in 0..exp.to_u64().unwrap() % 50 {
+                expected = &expected * &base % &m;
+            }
+            let exp = BigUint::from(exp.to_u64().unwrap() % 50);
+
+            assert_eq!(base.modpow(&exp, &m), expected, "{base:?}^{exp:?} mod {m:?}");
+        }
+    }
+
+    #[test]
+    fn roots() {
+        assert_eq!(BigUint::from(26u64).nth_root(3), BigUint::from(2u64));
+        assert_eq!(BigUint::from(27u64).nth_root(3), BigUint::from(3u64));
+        assert_eq!(BigUint::from(27u64).exact_nth_root(3), Some(BigUint::from(3u64)));
+        assert_eq!(BigUint::from(28u64).exact_nth_root(3), None);
+        assert_eq!(BigUint::zero().nth_root(5), BigUint::zero());
+
+        let mut rng = rand::thread_rng();
+        for k in 2..6 {
+            let x = BigUint::random_bits(300, &mut rng);
+            assert_eq!(x.pow(k).exact_nth_root(k), Some(x.clone()));
+
+            // Just shy of a perfect power rounds down
+            let r = (x.pow(k) - 1).nth_root(k);
+            assert_eq!(r, &x - 1);
+        }
+    }
+
+    #[test]
+    fn signed() {
+        let int = |negative, x: u64| BigInt::new(negative, BigUint::from(x));
+
+        assert_eq!(&int(false, 3) - &int(false, 5), int(true, 2));
+        assert_eq!(&int(true, 3) + &int(false, 5), int(false, 2));
+        assert_eq!(&int(true, 3) * &int(true, 5), int(false, 15));
+        assert_eq!(&int(true, 3) - &int(true, 3), BigInt::default());
+        assert!(!(&int(true, 3) + &int(false, 3)).is_negative());
+
+        assert_eq!(int(true, 3).rem_euclid(&BigUint::from(10u64)), BigUint::from(7u64));
+        assert_eq!(int(true, 20).rem_euclid(&BigUint::from(10u64)), BigUint::zero());
+        assert_eq!(int(true, 3).to_string(), "-3");
+    }
+
+    #[test]
+    fn random() {
+        let mut rng = rand::thread_rng();
+        let bound = hex("10000000000000000000001");
+        for _ in 0..100 {
+            assert!(BigUint::random_below(&bound, &mut rng) < bound);
+        }
+
+        assert!(BigUint::random_bits(65, &mut rng).bits() <= 65);
+    }
+}
@@ -0,0 +1,184 @@
+//! Finite-field Diffie–Hellman.
+use rand::Rng;
+
+use crate::bigint::BigUint;
+use crate::set_four::{challenge_thirty::MerkleDamgard, challenge_twenty_eight::Sha1};
+
+// RFC 3526 MODP groups. All of them use g = 2.
+const MODP_1536: &str = "
+    FFFFFFFF FFFFFFFF C90FDAA2 2168C234 C4C6628B 80DC1CD1 29024E08 8A67CC74
+    020BBEA6 3B139B22 514A0879 8E3404DD EF9519B3 CD3A431B 302B0A6D F25F1437
+    4FE1356D 6D51C245 E485B576 625E7EC6 F44C42E9 A637ED6B 0BFF5CB6 F406B7ED
+    EE386BFB 5A899FA5 AE9F2411 7C4B1FE6 49286651 ECE45B3D C2007CB8 A163BF05
+    98DA4836 1C55D39A 69163FA8 FD24CF5F 83655D23 DCA3AD96 1C62F356 208552BB
+    9ED52907 7096966D 670C354E 4ABC9804 F1746C08 CA237327 FFFFFFFF FFFFFFFF";
+
+const MODP_2048: &str = "
+    FFFFFFFF FFFFFFFF C90FDAA2 2168C234 C4C6628B 80DC1CD1 29024E08 8A67CC74
+    020BBEA6 3B139B22 514A0879 8E3404DD EF9519B3 CD3A431B 302B0A6D F25F1437
+    4FE1356D 6D51C245 E485B576 625E7EC6 F44C42E9 A637ED6B 0BFF5CB6 F406B7ED
+    EE386BFB 5A899FA5 AE9F2411 7C4B1FE6 49286651 ECE45B3D C2007CB8 A163BF05
+    98DA4836 1C55D39A 69163FA8 FD24CF5F 83655D23 DCA3AD96 1C62F356 208552BB
+    9ED52907 7096966D 670C354E 4ABC9804 F1746C08 CA18217C 32905E46 2E36CE3B
+    E39E772C 180E8603 9B2783A2 EC07A28F B5C55DF0 6F4C52C9 DE2BCBF6 95581718
+    3995497C EA956AE5 15D22618 98FA0510 15728E5A 8AACAA68 FFFFFFFF FFFFFFFF";
+
+const MODP_3072: &str = "
+    FFFFFFFF FFFFFFFF C90FDAA2 2168C234 C4C6628B 80DC1CD1 29024E08 8A67CC74
+    020BBEA6 3B139B22 514A0879 8E3404DD EF9519B3 CD3A431B 302B0A6D F25F1437
+    4FE1356D 6D51C245 E485B576 625E7EC6 F44C42E9 A637ED6B 0BFF5CB6 F406B7ED
+    EE386BFB 5A899FA5 AE9F2411 7C4B1FE6 49286651 ECE45B3D C2007CB8 A163BF05
+    98DA4836 1C55D39A 69163FA8 FD24CF5F 83655D23 DCA3AD96 1C62F356 208552BB
+    9ED52907 7096966D 670C354E 4ABC9804 F1746C08 CA18217C 32905E46 2E36CE3B
+    E39E772C 180E8603 9B2783A2 EC07A28F B5C55DF0 6F4C52C9 DE2BCBF6 95581718
+    3995497C EA956AE5 15D22618 98FA0510 15728E5A 8AAAC42D AD33170D 04507A33
+    A85521AB DF1CBA64 ECFB8504 58DBEF0A 8AEA7157 5D060C7D B3970F85 A6E1E4C7
+    ABF5AE8C DB0933D7 1E8C94E0 4A25619D CEE3D226 1AD2EE6B F12FFA06 D98A0864
+    D8760273 3EC86A64 521F2B18 177B200C BBE11757 7A615D6C 770988C0 BAD946E2
+    08E24FA0 74E5AB31 43DB5BFC E0FD108E 4B82D120 A93AD2CA FFFFFFFF FFFFFFFF";
+
+/// A prime modulus and a generator.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Group {
+    pub p: BigUint,
+    pub g: BigUint,
+}
+
+impl Group {
+    pub fn new(p: BigUint, g: BigUint) -> Self {
+        Self { p, g }
+    }
+
+    /// The 1536-bit group from challenge 33, which is RFC 3526's group 5.
+    pub fn cryptopals() -> Self {
+        Self::modp_1536()
+    }
+
+    pub fn modp_1536() -> Self {
+        Self::rfc3526(MODP_1536)
+    }
+
+    pub fn modp_2048() -> Self {
+        Self::rfc3526(MODP_2048)
+    }
+
+    pub fn modp_3072() -> Self {
+        Self::rfc3526(MODP_3072)
+    }
+
+    fn rfc3526(p: &str) -> Self {
+        let p = BigUint::from_hex(p.as_bytes()).expect("bad group constant");
+        Self::new(p, BigUint::from(2u64))
+    }
+
+    pub fn generate_keypair(&self) -> Keypair {
+        self.generate_keypair_with(&mut rand::thread_rng())
+    }
+
+    /// A keypair from a caller's RNG, seeded for reproducible tests.
+    pub fn generate_keypair_with<R: Rng + ?Sized>(&self, rng: &mut R) -> Keypair {
+        // Anything in [1, p - 2]
+        let private = BigUint::random_below(&(&self.p - 2), rng) + 1;
+        Keypair::from_private(self.clone(), private)
+    }
+}
+
+/// One side of an exchange.
+#[derive(Clone, Debug)]
+pub struct Keypair {
+    group: Group,
+    private: BigUint,
+    public: BigUint,
+}
+
+impl Keypair {
+    pub fn from_private(group: Group, private: BigUint) -> Self {
+        let public = group.g.modpow(&private, &group.p);
+        Self { group, private, public }
+    }
+
+    pub fn group(&self) -> &Group {
+        &self.group
+    }
+
+    pub fn public(&self) -> &BigUint {
+        &self.public
+    }
+
+    /// their_public^private mod p.
+    pub fn shared_secret(&self, their_public: &BigUint) -> BigUint {
+        their_public.modpow(&self.private, &self.group.p)
+    }
+
+    /// The AES key for talking to the owner of `their_public`.
+    pub fn session_key(&self, their_public: &BigUint) -> Vec<u8> {
+        derive_key(&self.shared_secret(their_public))
+    }
+}
+
+/// Turns a shared secret into an AES-128 key: the first 16 bytes of SHA-1
+/// over its big endian bytes.
+pub fn derive_key(secret: &BigUint) -> Vec<u8> {
+    Sha1::digest(&secret.to_bytes_be())[..16].to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use crate::prime::is_probable_prime;
+    use crate::set_two::{challenge_eleven::aes_cbc_enc, challenge_ten::aes_cbc_dec, challenge_fifteen::strip_pkcs7};
+
+    fn exchange(group: &Group) {
+        let alice = group.generate_keypair();
+        let bob = group.generate_keypair();
+
+        assert_eq!(alice.shared_secret(bob.public()), bob.shared_secret(alice.public()));
+        assert_eq!(alice.session_key(bob.public()), bob.session_key(alice.public()));
+    }
+
+    #[test]
+    fn toy_group() {
+        let group = Group::new(BigUint::from(37u64), BigUint::from(5u64));
+        for _ in 0..20 {
+            exchange(&group);
+        }
+
+        // 5^6 = 15625 = 11 mod 37, and 11^6 = 1771561 = 1 mod 37
+        let alice = Keypair::from_private(group.clone(), BigUint::from(6u64));
+        assert_eq!(alice.public(), &BigUint::from(11u64));
+        assert_eq!(alice.shared_secret(&BigUint::from(11u64)).to_u64(), Some(1));
+    }
+
+    #[test]
+    fn groups() {
+        for (group, bits) in [(Group::modp_1536(), 1536), (Group::modp_2048(), 2048), (Group::modp_3072(), 3072)] {
+            assert_eq!(group.p.bits(), bits);
+            assert_eq!(group.g, BigUint::from(2u64));
+        }
+
+        // Safe primes, both halves
+        let mut rng = rand::thread_rng();
+        let p = Group::cryptopals().p;
+        assert!(is_probable_prime(&p, 2, &mut rng));
+        assert!(is_probable_prime(&(&p >> 1), 2, &mut rng));
+    }
+
+    #[test]
+    fn example() {
+        exchange(&Group::cryptopals());
+
+        // Both ends get a key that works with the CBC code
+        let mut rng = rand::rngs::StdRng::seed_from_u64(33);
+        let group = Group::cryptopals();
+        let alice = group.generate_keypair_with(&mut rng);
+        let bob = group.generate_keypair_with(&mut rng);
+
+        let key = alice.session_key(bob.public());
+        assert_eq!(key.len(), 16);
+
+        let (iv, ciphertext) = aes_cbc_enc(b"ice ice baby", &key);
+        let plaintext = aes_cbc_dec(&ciphertext, &bob.session_key(alice.public()), &iv).unwrap();
+        assert_eq!(strip_pkcs7(&plaintext).unwrap(), b"ice ice baby");
+    }
+}
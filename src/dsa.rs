@@ -0,0 +1,161 @@
+//! DSA over SHA-1.
+use rand::Rng;
+
+use crate::bigint::BigUint;
+use crate::rsa::invmod;
+use crate::set_four::{challenge_thirty::MerkleDamgard, challenge_twenty_eight::Sha1};
+
+// The 1024-bit p, 160-bit q group from challenge 43
+const P: &str = "
+    800000000000000089e1855218a0e7dac38136ffafa72eda7859f2171e25e65eac698c1702578b07
+    dc2a1076da241c76c62d374d8389ea5aeffd3226a0530cc565f3bf6b50929139ebeac04f48c3c84a
+    fb796d61e5a4f9a8fda812ab59494232c7d2b4deb50aa18ee9e132bfa85ac4374d7f9091abc3d015
+    efc871a584471bb1";
+
+const Q: &str = "f4f47f05794b256174bba6e9b396a7707e563c5b";
+
+const G: &str = "
+    5958c9d3898b224b12672c0b98e06c60df923cb8bc999d119458fef538b8fa4046c8db53039db620
+    c094c9fa077ef389b5322a559946a71903f990f1f7e0e025e2d7f7cf494aff1a0470f5b64c36b625
+    a097f1651fe775323556fe00b3608c887892878480e99041be601a62166ca6894bdd41a7054ec89f
+    756ba9fc95302291";
+
+/// A prime p, a prime q dividing p - 1, and a g of order q.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Params {
+    pub p: BigUint,
+    pub q: BigUint,
+    pub g: BigUint,
+}
+
+impl Params {
+    pub fn new(p: BigUint, q: BigUint, g: BigUint) -> Self {
+        Self { p, q, g }
+    }
+
+    pub fn cryptopals() -> Self {
+        let parse = |x: &str| BigUint::from_hex(x.as_bytes()).expect("bad DSA constant");
+        Self::new(parse(P), parse(Q), parse(G))
+    }
+
+    pub fn generate_keypair(&self) -> Keypair {
+        self.generate_keypair_with(&mut rand::thread_rng())
+    }
+
+    /// A keypair from a caller's RNG, seeded for reproducible tests.
+    pub fn generate_keypair_with<R: Rng + ?Sized>(&self, rng: &mut R) -> Keypair {
+        // Anything in [1, q - 1]
+        let x = BigUint::random_below(&(&self.q - 1), rng) + 1;
+        Keypair::from_private(self.clone(), x)
+    }
+}
+
+/// H(m): SHA-1 read as a big endian number.
+pub fn hash_message(msg: &[u8]) -> BigUint {
+    BigUint::from_bytes_be(&Sha1::digest(msg))
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Signature {
+    pub r: BigUint,
+    pub s: BigUint,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PublicKey {
+    pub params: Params,
+    pub y: BigUint,
+}
+
+impl PublicKey {
+    /// Checks 0 < r, s < q, then that (g^u1 * y^u2 mod p) mod q = r.
+    pub fn verify(&self, msg: &[u8], signature: &Signature) -> bool {
+        let q = &self.params.q;
+        let in_range = |x: &BigUint| !x.is_zero() && x < q;
+
+        in_range(&signature.r) && in_range(&signature.s) && self.verify_unchecked(msg, signature)
+    }
+
+    /// `verify` without the range checks, which lets r = 0 through.
+    pub fn verify_unchecked(&self, msg: &[u8], signature: &Signature) -> bool {
+        let Params { p, q, g } = &self.params;
+        let Some(w) = invmod(&signature.s, q) else { return false };
+
+        let u1 = hash_message(msg) * &w % q;
+        let u2 = &signature.r * &w % q;
+        let v = g.modpow(&u1, p) * self.y.modpow(&u2, p) % p % q;
+
+        v == signature.r
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Keypair {
+    pub public: PublicKey,
+    x: BigUint,
+}
+
+impl Keypair {
+    pub fn from_private(params: Params, x: BigUint) -> Self {
+        let y = params.g.modpow(&x, &params.p);
+        Self { public: PublicKey { params, y }, x }
+    }
+
+    /// Signs with a fresh random k. Doesn't bother drawing again if r or s
+    /// comes out 0, which with honest parameters just doesn't happen.
+    pub fn sign(&self, msg: &[u8]) -> Signature {
+        let q = &self.public.params.q;
+        let k = BigUint::random_below(&(q - 1), &mut rand::thread_rng()) + 1;
+
+        self.sign_with_nonce(msg, &k)
+    }
+
+    /// r = (g^k mod p) mod q, s = k^-1 * (H(m) + x * r) mod q. Anyone who
+    /// learns k learns x, so this is only for tests and attacks.
+    pub fn sign_with_nonce(&self, msg: &[u8], k: &BigUint) -> Signature {
+        let Params { p, q, g } = &self.public.params;
+        let k_inv = invmod(k, q).expect("k must be invertible mod q");
+
+        let r = g.modpow(k, p) % q;
+        let s = k_inv * (hash_message(msg) + &self.x * &r) % q;
+
+        Signature { r, s }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prime::{is_probable_prime, DEFAULT_ROUNDS};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn params() {
+        let Params { p, q, g } = Params::cryptopals();
+        let mut rng = rand::thread_rng();
+
+        assert_eq!((p.bits(), q.bits()), (1024, 160));
+        assert!(is_probable_prime(&p, DEFAULT_ROUNDS, &mut rng));
+        assert!(is_probable_prime(&q, DEFAULT_ROUNDS, &mut rng));
+        assert!(((&p - 1) % &q).is_zero());
+        assert!(g.modpow(&q, &p).is_one());
+    }
+
+    #[test]
+    fn example() {
+        let key = Params::cryptopals().generate_keypair_with(&mut StdRng::seed_from_u64(43));
+
+        let signature = key.sign(b"attack at dawn");
+        assert!(key.public.verify(b"attack at dawn", &signature));
+        assert!(!key.public.verify(b"attack at dusk", &signature));
+
+        let other = Params::cryptopals().generate_keypair();
+        assert!(!other.public.verify(b"attack at dawn", &signature));
+
+        // Out of range values get turned away
+        let q = &key.public.params.q;
+        let shifted = Signature { r: signature.r.clone(), s: &signature.s + q };
+        assert!(!key.public.verify(b"attack at dawn", &shifted));
+        assert!(key.public.verify_unchecked(b"attack at dawn", &shifted));
+    }
+}
@@ -1,3 +1,12 @@
 #![feature(iterator_try_reduce, iter_array_chunks, array_chunks)]
 pub mod set_one { automod::dir!(pub "src/set_one"); }
 pub mod set_two { automod::dir!(pub "src/set_two"); }
+pub mod set_three { automod::dir!(pub "src/set_three"); }
+pub mod set_four { automod::dir!(pub "src/set_four"); }
+pub mod set_five { automod::dir!(pub "src/set_five"); }
+pub mod set_six { automod::dir!(pub "src/set_six"); }
+pub mod bigint;
+pub mod prime;
+pub mod dh;
+pub mod rsa;
+pub mod dsa;
@@ -0,0 +1,267 @@
+//! Primality testing and prime generation over `BigUint`.
+use std::sync::OnceLock;
+
+use rand::{rngs::{StdRng, ThreadRng}, Rng, SeedableRng};
+
+use crate::bigint::BigUint;
+
+/// Miller–Rabin rounds unless told otherwise. A composite survives each
+/// round with probability at most 1/4, so this leaves 2^-64.
+pub const DEFAULT_ROUNDS: usize = 32;
+
+/// Primes below this get used for trial division
+const SIEVE_LIMIT: usize = 2048;
+
+/// The primes below 2048, by the sieve of Eratosthenes.
+pub fn small_primes() -> &'static [u64] {
+    static PRIMES: OnceLock<Vec<u64>> = OnceLock::new();
+
+    PRIMES.get_or_init(|| {
+        let mut composite = vec![false; SIEVE_LIMIT];
+        (2..SIEVE_LIMIT)
+            .filter(|i| {
+                if composite[*i] {
+                    return false;
+                }
+                for multiple in (i * i..SIEVE_LIMIT).step_by(*i) {
+                    composite[multiple] = true;
+                }
+                true
+            })
+            .map(|i| i as u64)
+            .collect()
+    })
+}
+
+/// Settles primality by dividing by the small primes, if it can: Some(true)
+/// for a small prime, Some(false) for anything with a small factor, and None
+/// when it takes a real test.
+pub fn trial_division(n: &BigUint) -> Option<bool> {
+    if let Some(n) = n.to_u64().filter(|n| *n < SIEVE_LIMIT as u64) {
+        return Some(small_primes().binary_search(&n).is_ok());
+    }
+
+    if small_primes().iter().any(|p| (n % *p).is_zero()) {
+        return Some(false);
+    }
+
+    None
+}
+
+/// Miller–Rabin with `rounds` random bases. Expects an odd n above 3.
+pub fn miller_rabin<R: Rng + ?Sized>(n: &BigUint, rounds: usize, rng: &mut R) -> bool {
+    // Nowhere to pick a base from otherwise, and d * 2^s needs n - 1 even
+    assert!(n.is_odd() && *n > BigUint::from(3u64), "miller_rabin needs an odd n above 3, got {n}");
+
+    let n_minus_one = n - 1;
+    let n_minus_three = n - 3;
+
+    // n - 1 = d * 2^s with d odd
+    let s = n_minus_one.trailing_zeros();
+    let d = &n_minus_one >> s;
+
+    'witness: for _ in 0..rounds {
+        // A base in [2, n - 2]
+        let a = BigUint::random_below(&n_minus_three, rng) + 2;
+
+        let mut x = a.modpow(&d, n);
+        if x.is_one() || x == n_minus_one {
+            continue;
+        }
+
+        for _ in 1..s {
+            x = &x * &x % n;
+            if x == n_minus_one {
+                continue 'witness;
+            }
+        }
+
+        return false;
+    }
+
+    true
+}
+
+/// Trial division, then Miller–Rabin for whatever survives.
+pub fn is_probable_prime<R: Rng + ?Sized>(n: &BigUint, rounds: usize, rng: &mut R) -> bool {
+    trial_division(n).unwrap_or_else(|| miller_rabin(n, rounds, rng))
+}
+
+/// Makes primes from a source of randomness. Seed it to get the same primes
+/// (and so the same keys) every run.
+pub struct PrimeGenerator<R: Rng> {
+    rng: R,
+    rounds: usize,
+}
+
+impl PrimeGenerator<ThreadRng> {
+    pub fn new() -> Self {
+        Self::from_rng(rand::thread_rng())
+    }
+}
+
+impl Default for PrimeGenerator<ThreadRng> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PrimeGenerator<StdRng> {
+    /// Deterministic, for reproducible keys in tests.
+    pub fn seeded(seed: u64) -> Self {
+        Self::from_rng(StdRng::seed_from_u64(seed))
+    }
+}
+
+impl<R: Rng> PrimeGenerator<R> {
+    pub fn from_rng(rng: R) -> Self {
+        Self { rng, rounds: DEFAULT_ROUNDS }
+    }
+
+    /// Miller–Rabin rounds per candidate.
+    pub fn with_rounds(mut self, rounds: usize) -> Self {
+        self.rounds = rounds;
+        self
+    }
+
+    /// The underlying RNG, for drawing anything else a key needs.
+    pub fn rng(&mut self) -> &mut R {
+        &mut self.rng
+    }
+
+    pub fn is_prime(&mut self, n: &BigUint) -> bool {
+        is_probable_prime(n, self.rounds, &mut self.rng)
+    }
+
+    /// A random prime of exactly `bits` bits. The top two bits are both set,
+    /// so the product of two of these has exactly twice as many.
+    pub fn random_prime(&mut self, bits: usize) -> BigUint {
+        assert!(bits >= 3, "no room for a {bits} bit prime");
+
+        loop {
+            let candidate = self.candidate(bits);
+            if self.is_prime(&candidate) {
+                return candidate;
+            }
+        }
+    }
+
+    /// A random prime p of exactly `bits` bits where (p - 1) / 2 is prime
+    /// too.
+    pub fn safe_prime(&mut self, bits: usize) -> BigUint {
+        assert!(bits >= 4, "no room for a {bits} bit safe prime");
+
+        loop {
+            // Only q's top bit is forced, which is all p needs to come out
+            // `bits` long. Forcing two as `candidate` does would leave no
+            // safe primes at all to find at 4 and 5 bits.
+            let mut q = BigUint::random_bits(bits - 1, &mut self.rng);
+            q.set_bit(bits - 2, true);
+            q.set_bit(0, true);
+            let p = &(&q << 1) + 1;
+
+            // Both need to dodge every small prime, which is much cheaper to
+            // check than either Miller–Rabin
+            if trial_division(&q) == Some(false) || trial_division(&p) == Some(false) {
+                continue;
+            }
+
+            if self.is_prime(&q) && self.is_prime(&p) {
+                return p;
+            }
+        }
+    }
+
+    // Random and odd, with the top two bits set
+    fn candidate(&mut self, bits: usize) -> BigUint {
+        let mut candidate = BigUint::random_bits(bits, &mut self.rng);
+        candidate.set_bit(bits - 1, true);
+        candidate.set_bit(bits - 2, true);
+        candidate.set_bit(0, true);
+
+        candidate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_prime(n: &BigUint) -> bool {
+        is_probable_prime(n, DEFAULT_ROUNDS, &mut rand::thread_rng())
+    }
+
+    #[test]
+    fn known_primes() {
+        assert_eq!(small_primes()[..5], [2, 3, 5, 7, 11]);
+        assert_eq!(small_primes().last(), Some(&2039));
+
+        for p in [2u64, 3, 2039, 2053, 1_000_000_007] {
+            assert!(is_prime(&BigUint::from(p)), "{p}");
+        }
+
+        // Straight to Miller–Rabin, from the smallest n it takes
+        for p in [5u64, 7, 2039] {
+            assert!(miller_rabin(&BigUint::from(p), DEFAULT_ROUNDS, &mut rand::thread_rng()), "{p}");
+        }
+
+        // Mersenne primes, well past trial division
+        assert!(is_prime(&((BigUint::one() << 127) - 1)));
+        assert!(is_prime(&((BigUint::one() << 521) - 1)));
+    }
+
+    #[test]
+    fn known_composites() {
+        for n in [0u64, 1, 4, 2047 * 2053, 561, 41041, 3_215_031_751] {
+            assert!(!is_prime(&BigUint::from(n)), "{n}");
+        }
+
+        for n in [9u64, 15, 561] {
+            assert!(!miller_rabin(&BigUint::from(n), DEFAULT_ROUNDS, &mut rand::thread_rng()), "{n}");
+        }
+
+        // A Carmichael number, (6k + 1)(12k + 1)(18k + 1) with all three
+        // prime, so it fools Fermat but not Miller–Rabin
+        let carmichael = "1296000004358844004886708077826165821249".parse::<BigUint>().unwrap();
+        assert_eq!(trial_division(&carmichael), None);
+        assert!(!is_prime(&carmichael));
+
+        // 2^128 + 1 isn't prime, and neither is a product of two big primes
+        assert!(!is_prime(&((BigUint::one() << 128) + 1)));
+        let mut primes = PrimeGenerator::new();
+        let product = primes.random_prime(64) * primes.random_prime(64);
+        assert!(!primes.is_prime(&product));
+    }
+
+    #[test]
+    fn generation() {
+        let mut primes = PrimeGenerator::new().with_rounds(8);
+
+        let p = primes.random_prime(256);
+        assert_eq!(p.bits(), 256);
+        assert!(is_prime(&p));
+
+        let p = primes.safe_prime(64);
+        assert_eq!(p.bits(), 64);
+        assert!(is_prime(&p));
+        assert!(is_prime(&(&p >> 1)));
+
+        // 11 is the only 4 bit safe prime
+        assert_eq!(primes.safe_prime(4), BigUint::from(11u64));
+        for bits in 5..=12 {
+            let p = primes.safe_prime(bits);
+            assert_eq!(p.bits(), bits);
+            assert!(is_prime(&p) && is_prime(&(&p >> 1)), "{p} isn't a safe prime");
+        }
+    }
+
+    #[test]
+    fn seeded() {
+        let p = PrimeGenerator::seeded(1337).random_prime(128);
+        let q = PrimeGenerator::seeded(1337).random_prime(128);
+        let r = PrimeGenerator::seeded(1338).random_prime(128);
+
+        assert_eq!(p, q);
+        assert_ne!(p, r);
+    }
+}
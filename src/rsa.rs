@@ -0,0 +1,245 @@
+//! Textbook RSA: no padding, just m^e mod n.
+use eyre::{ensure, eyre, Result};
+use rand::{rngs::ThreadRng, Rng};
+
+use crate::bigint::{BigInt, BigUint};
+use crate::prime::PrimeGenerator;
+
+/// Extended Euclid: (g, x, y) with a * x + b * y = g = gcd(a, b).
+pub fn egcd(a: &BigUint, b: &BigUint) -> (BigUint, BigInt, BigInt) {
+    let (mut old_r, mut r) = (a.clone(), b.clone());
+    let (mut old_x, mut x) = (BigInt::from(BigUint::one()), BigInt::default());
+    let (mut old_y, mut y) = (BigInt::default(), BigInt::from(BigUint::one()));
+
+    while !r.is_zero() {
+        let (q, rem) = old_r.divmod(&r);
+        let q = BigInt::from(q);
+
+        old_r = std::mem::replace(&mut r, rem);
+        let next_x = &old_x - &(&q * &x);
+        old_x = std::mem::replace(&mut x, next_x);
+        let next_y = &old_y - &(&q * &y);
+        old_y = std::mem::replace(&mut y, next_y);
+    }
+
+    (old_r, old_x, old_y)
+}
+
+/// The x with a * x = 1 mod m, if there is one.
+pub fn invmod(a: &BigUint, m: &BigUint) -> Option<BigUint> {
+    a.modinv(m)
+}
+
+/// Chinese Remainder Theorem: given (residue, modulus) pairs with pairwise
+/// coprime moduli, the x below their product that leaves each residue.
+/// Returns x and the product, or None if two moduli share a factor.
+pub fn crt(congruences: &[(BigUint, BigUint)]) -> Option<(BigUint, BigUint)> {
+    congruences
+        .iter()
+        .try_fold((BigUint::zero(), BigUint::one()), |(x, n), (residue, modulus)| {
+            // Fold in one congruence at a time: find the t with
+            // x + n * t = residue mod modulus
+            let n_inv = invmod(&(&n % modulus), modulus)?;
+            let diff = (residue % modulus + modulus - &x % modulus) % modulus;
+            let t = diff * n_inv % modulus;
+
+            Some((x + &n * t, n * modulus))
+        })
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PublicKey {
+    pub n: BigUint,
+    pub e: BigUint,
+}
+
+impl PublicKey {
+    /// Bytes in the modulus, and so in every ciphertext.
+    pub fn size(&self) -> usize {
+        self.n.bits().div_ceil(8)
+    }
+
+    /// m^e mod n. The message should be below n.
+    pub fn encrypt(&self, m: &BigUint) -> BigUint {
+        m.modpow(&self.e, &self.n)
+    }
+
+    /// Encrypts `msg` read as a big endian number, giving a ciphertext as
+    /// long as the modulus.
+    pub fn encrypt_bytes(&self, msg: &[u8]) -> Result<Vec<u8>> {
+        let m = BigUint::from_bytes_be(msg);
+        ensure!(m < self.n, "message doesn't fit under the modulus");
+
+        Ok(self.encrypt(&m).to_bytes_be_padded(self.size()))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PrivateKey {
+    pub n: BigUint,
+    pub d: BigUint,
+    p: BigUint,
+    q: BigUint,
+    // d mod (p - 1), d mod (q - 1) and q^-1 mod p, for the CRT
+    dp: BigUint,
+    dq: BigUint,
+    q_inv: BigUint,
+}
+
+impl PrivateKey {
+    /// c^d mod n, done as two half-size exponentiations mod p and q and
+    /// stitched back together with Garner's formula.
+    pub fn decrypt(&self, c: &BigUint) -> BigUint {
+        let m1 = c.modpow(&self.dp, &self.p);
+        let m2 = c.modpow(&self.dq, &self.q);
+
+        // h = q^-1 * (m1 - m2) mod p
+        let diff = (&m1 + &self.p - &m2 % &self.p) % &self.p;
+        let h = &self.q_inv * &diff % &self.p;
+
+        m2 + h * &self.q
+    }
+
+    /// Decrypts a ciphertext from `PublicKey::encrypt_bytes`. Leading zero
+    /// bytes of the message don't survive.
+    pub fn decrypt_bytes(&self, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        let c = BigUint::from_bytes_be(ciphertext);
+        ensure!(c < self.n, "ciphertext doesn't fit under the modulus");
+
+        Ok(self.decrypt(&c).to_bytes_be())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Keypair {
+    pub public: PublicKey,
+    pub private: PrivateKey,
+}
+
+impl Keypair {
+    /// A fresh key with an n of `bits` bits.
+    pub fn generate(bits: usize, e: u64) -> Self {
+        Self::generate_with(bits, e, &mut PrimeGenerator::<ThreadRng>::new())
+    }
+
+    /// A fresh key from a caller's prime generator, seeded for
+    /// reproducible tests. e must be odd and above 1: an even e shares a
+    /// factor with every φ, so no key would ever turn up.
+    pub fn generate_with<R: Rng>(bits: usize, e: u64, primes: &mut PrimeGenerator<R>) -> Self {
+        assert!(e > 1 && e % 2 == 1, "e = {e} can't be an RSA exponent");
+
+        // Primes where e divides p - 1 leave e with no inverse, so just
+        // draw again
+        loop {
+            let p = primes.random_prime(bits / 2);
+            let q = primes.random_prime(bits - bits / 2);
+            if p == q {
+                continue;
+            }
+
+            if let Ok(key) = Self::from_primes(p, q, BigUint::from(e)) {
+                return key;
+            }
+        }
+    }
+
+    /// Builds a key from its primes. Fails if e has no inverse mod
+    /// φ(n) = (p - 1)(q - 1).
+    pub fn from_primes(p: BigUint, q: BigUint, e: BigUint) -> Result<Self> {
+        let n = &p * &q;
+        let phi = (&p - 1) * (&q - 1);
+        let d = invmod(&e, &phi).ok_or_else(|| eyre!("gcd(e, φ) isn't 1"))?;
+
+        let dp = &d % &(&p - 1);
+        let dq = &d % &(&q - 1);
+        let q_inv = invmod(&q, &p).ok_or_else(|| eyre!("p and q share a factor"))?;
+
+        Ok(Self {
+            public: PublicKey { n: n.clone(), e },
+            private: PrivateKey { n, d, p, q, dp, dq, q_inv },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn int(x: u64) -> BigUint {
+        BigUint::from(x)
+    }
+
+    #[test]
+    fn helpers() {
+        let (g, x, y) = egcd(&int(240), &int(46));
+        assert_eq!(g, int(2));
+        assert_eq!((x.to_string(), y.to_string()), ("-9".to_string(), "47".to_string()));
+
+        assert_eq!(invmod(&int(17), &int(3120)), Some(int(2753)));
+        assert_eq!(invmod(&int(3), &int(6)), None);
+
+        // Something big enough to wrap around a few times
+        let mut rng = rand::thread_rng();
+        let m = (BigUint::one() << 127) - 1;
+        let a = BigUint::random_below(&(&m - 1), &mut rng) + 1;
+        assert_eq!(&a * invmod(&a, &m).unwrap() % &m, int(1));
+    }
+
+    #[test]
+    fn chinese_remainder() {
+        // x = 2 mod 3, 3 mod 5, 2 mod 7
+        let (x, n) = crt(&[(int(2), int(3)), (int(3), int(5)), (int(2), int(7))]).unwrap();
+        assert_eq!((x, n), (int(23), int(105)));
+
+        assert_eq!(crt(&[(int(1), int(4)), (int(3), int(6))]), None);
+        assert_eq!(crt(&[]), Some((int(0), int(1))));
+    }
+
+    #[test]
+    fn textbook() {
+        // The example off Wikipedia
+        let key = Keypair::from_primes(int(61), int(53), int(17)).unwrap();
+        assert_eq!(key.public.n, int(3233));
+        assert_eq!(key.private.d, int(2753));
+
+        assert_eq!(key.public.encrypt(&int(65)), int(2790));
+        assert_eq!(key.private.decrypt(&int(2790)), int(65));
+    }
+
+    #[test]
+    fn rejects_bad_e() {
+        // 3 divides 7 - 1, then 13 - 1
+        assert!(Keypair::from_primes(int(7), int(11), int(3)).is_err());
+        assert!(Keypair::from_primes(int(11), int(13), int(3)).is_err());
+        assert!(Keypair::from_primes(int(11), int(17), int(65537)).is_ok());
+    }
+
+    fn round_trip(key: &Keypair) {
+        let mut rng = rand::thread_rng();
+        for _ in 0..10 {
+            let m = BigUint::random_below(&key.public.n, &mut rng);
+            let c = key.public.encrypt(&m);
+
+            assert_eq!(key.private.decrypt(&c), m);
+            assert_eq!(key.private.decrypt(&c), c.modpow(&key.private.d, &key.private.n));
+        }
+
+        let ciphertext = key.public.encrypt_bytes(b"attack at dawn").unwrap();
+        assert_eq!(ciphertext.len(), key.public.size());
+        assert_eq!(key.private.decrypt_bytes(&ciphertext).unwrap(), b"attack at dawn");
+
+        assert!(key.public.encrypt_bytes(&vec![0xFF; key.public.size()]).is_err());
+    }
+
+    #[test]
+    fn example() {
+        let key = Keypair::generate_with(512, 3, &mut PrimeGenerator::seeded(39));
+        assert_eq!(key.public.n.bits(), 512);
+        assert_eq!(key.public.e, int(3));
+        round_trip(&key);
+
+        let key = Keypair::generate(1024, 65537);
+        assert_eq!(key.public.n.bits(), 1024);
+        round_trip(&key);
+    }
+}
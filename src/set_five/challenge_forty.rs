@@ -0,0 +1,51 @@
+use eyre::{ensure, ContextCompat, Result};
+
+use crate::bigint::BigUint;
+use crate::rsa::{crt, PublicKey};
+
+/// Recovers a message encrypted unpadded to e different keys that all use
+/// the same small e. The CRT turns the ciphertexts into m^e mod n1 * n2 * ...,
+/// and since m^e is smaller than that, it's just m^e, so take the e-th root.
+pub fn broadcast_attack(intercepted: &[(PublicKey, BigUint)]) -> Result<Vec<u8>> {
+    let e = &intercepted.first().context("nothing intercepted")?.0.e;
+    ensure!(intercepted.iter().all(|(key, _)| &key.e == e), "keys use different exponents");
+
+    let e = e.to_u64().filter(|e| *e <= u32::MAX as u64).context("e is far too big")? as u32;
+    ensure!(intercepted.len() >= e as usize, "need {e} ciphertexts, got {}", intercepted.len());
+
+    let congruences = intercepted
+        .iter()
+        .take(e as usize)
+        .map(|(key, c)| (c.clone(), key.n.clone()))
+        .collect::<Vec<_>>();
+    let (m_e, _) = crt(&congruences).context("moduli share a factor")?;
+
+    let m = m_e.exact_nth_root(e).context("result isn't a perfect power")?;
+    Ok(m.to_bytes_be())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prime::PrimeGenerator;
+    use crate::rsa::Keypair;
+
+    #[test]
+    fn example() {
+        let msg = b"Spreading the word to anyone who'll listen";
+        let mut primes = PrimeGenerator::seeded(40);
+
+        let intercepted = (0..3)
+            .map(|_| {
+                let key = Keypair::generate_with(512, 3, &mut primes);
+                let c = key.public.encrypt(&BigUint::from_bytes_be(msg));
+                (key.public, c)
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(broadcast_attack(&intercepted).unwrap(), msg);
+
+        // Two isn't enough
+        assert!(broadcast_attack(&intercepted[..2]).is_err());
+    }
+}
@@ -0,0 +1,223 @@
+use std::collections::HashMap;
+
+use eyre::{eyre, Result};
+use rand::Rng;
+
+use crate::bigint::BigUint;
+use super::challenge_thirty_four::Channel;
+use super::challenge_thirty_six::{password_exponent, proof, session_key, SrpParams};
+
+const WORDS: &str = include_str!("../../vendor/words.txt");
+
+/// Simplified SRP drops k and sends a random u instead of hashing it out of
+/// A and B, which takes B out of the password's way.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SimpleSrpMessage {
+    /// C -> S: I, A
+    Hello { email: String, public: BigUint },
+    /// S -> C: salt, B = g^b, u
+    Challenge { salt: Vec<u8>, public: BigUint, u: BigUint },
+    /// C -> S: HMAC-SHA256(K, salt)
+    Proof([u8; 32]),
+    /// S -> C: whether the proof checked out
+    Verdict(bool),
+}
+
+// The server's record of a user
+struct Verifier {
+    salt: Vec<u8>,
+    v: BigUint,
+}
+
+pub struct SimpleSrpServer {
+    params: SrpParams,
+    users: HashMap<String, Verifier>,
+}
+
+impl SimpleSrpServer {
+    pub fn new(params: SrpParams) -> Self {
+        Self { params, users: HashMap::new() }
+    }
+
+    pub fn register(&mut self, email: &str, password: &[u8]) {
+        let salt = rand::thread_rng().gen::<[u8; 16]>().to_vec();
+        let v = self.params.g.modpow(&password_exponent(&salt, password), &self.params.n);
+
+        self.users.insert(email.to_string(), Verifier { salt, v });
+    }
+
+    /// Handles one login over `chan`. Returns whether the client got in.
+    pub fn serve<C: Channel<SimpleSrpMessage>>(&self, chan: &mut C) -> Result<bool> {
+        let SrpParams { n, g, .. } = &self.params;
+
+        let (email, client_public) = match chan.expect()? {
+            SimpleSrpMessage::Hello { email, public } => (email, public),
+            x => return Err(eyre!("expected hello, got {x:?}")),
+        };
+        let Some(user) = self.users.get(&email) else {
+            chan.send(SimpleSrpMessage::Verdict(false))?;
+            return Ok(false);
+        };
+
+        let mut rng = rand::thread_rng();
+        let b = BigUint::random_below(n, &mut rng);
+        let u = BigUint::random_bits(128, &mut rng);
+        chan.send(SimpleSrpMessage::Challenge { salt: user.salt.clone(), public: g.modpow(&b, n), u: u.clone() })?;
+
+        // S = (A * v^u)^b mod N
+        let secret = (&client_public * user.v.modpow(&u, n) % n).modpow(&b, n);
+        let expected = proof(&session_key(&secret), &user.salt);
+
+        let valid = match chan.expect()? {
+            SimpleSrpMessage::Proof(x) => x == expected,
+            x => return Err(eyre!("expected proof, got {x:?}")),
+        };
+        chan.send(SimpleSrpMessage::Verdict(valid))?;
+
+        Ok(valid)
+    }
+}
+
+pub struct SimpleSrpClient {
+    params: SrpParams,
+    email: String,
+    password: Vec<u8>,
+}
+
+impl SimpleSrpClient {
+    pub fn new(params: SrpParams, email: &str, password: &[u8]) -> Self {
+        Self { params, email: email.to_string(), password: password.to_vec() }
+    }
+
+    /// Logs in over `chan`. Returns whether the server let us in.
+    pub fn login<C: Channel<SimpleSrpMessage>>(&self, chan: &mut C) -> Result<bool> {
+        let SrpParams { n, g, .. } = &self.params;
+
+        let a = BigUint::random_below(n, &mut rand::thread_rng());
+        chan.send(SimpleSrpMessage::Hello { email: self.email.clone(), public: g.modpow(&a, n) })?;
+
+        let (salt, server_public, u) = match chan.expect()? {
+            SimpleSrpMessage::Challenge { salt, public, u } => (salt, public, u),
+            SimpleSrpMessage::Verdict(x) => return Ok(x),
+            x => return Err(eyre!("expected challenge, got {x:?}")),
+        };
+
+        // S = B^(a + u * x) mod N
+        let x = password_exponent(&salt, &self.password);
+        let secret = server_public.modpow(&(a + u * x), n);
+        chan.send(SimpleSrpMessage::Proof(proof(&session_key(&secret), &salt)))?;
+
+        match chan.expect()? {
+            SimpleSrpMessage::Verdict(x) => Ok(x),
+            x => Err(eyre!("expected verdict, got {x:?}")),
+        }
+    }
+}
+
+/// A password the dictionary attack turned up.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CrackedPassword {
+    pub email: String,
+    pub password: String,
+}
+
+/// Poses as the server with salt = "", B = g and u = 1, so the client's
+/// S = g^(a + x) = A * g^x, and the only unknown in its proof is the
+/// password. Tries every word in `vendor/words.txt` offline.
+pub struct DictionaryAttacker {
+    params: SrpParams,
+    words: Vec<&'static str>,
+}
+
+impl DictionaryAttacker {
+    pub fn new(params: SrpParams) -> Self {
+        Self { params, words: WORDS.lines().collect() }
+    }
+
+    pub fn words(&self) -> &[&'static str] {
+        &self.words
+    }
+
+    /// Takes one login over `chan`, turns it down and cracks the proof. None
+    /// if the password isn't in the list.
+    pub fn serve<C: Channel<SimpleSrpMessage>>(&self, chan: &mut C) -> Result<Option<CrackedPassword>> {
+        let SrpParams { n, g, .. } = &self.params;
+
+        let (email, client_public) = match chan.expect()? {
+            SimpleSrpMessage::Hello { email, public } => (email, public),
+            x => return Err(eyre!("expected hello, got {x:?}")),
+        };
+
+        let salt = Vec::new();
+        chan.send(SimpleSrpMessage::Challenge { salt: salt.clone(), public: g.clone(), u: BigUint::one() })?;
+
+        let client_proof = match chan.expect()? {
+            SimpleSrpMessage::Proof(x) => x,
+            x => return Err(eyre!("expected proof, got {x:?}")),
+        };
+        chan.send(SimpleSrpMessage::Verdict(false))?;
+
+        let password = self.words.iter().find(|word| {
+            let x = password_exponent(&salt, word.as_bytes());
+            let secret = &client_public * g.modpow(&x, n) % n;
+
+            proof(&session_key(&secret), &salt) == client_proof
+        });
+
+        Ok(password.map(|password| CrackedPassword { email, password: password.to_string() }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+    use rand::seq::SliceRandom;
+    use super::*;
+    use crate::set_five::challenge_thirty_four::MemoryChannel;
+
+    #[test]
+    fn honest() {
+        let mut server = SimpleSrpServer::new(SrpParams::cryptopals());
+        server.register("alice@example.com", b"hunter2");
+
+        for (password, expected) in [(&b"hunter2"[..], true), (b"hunter3", false)] {
+            let client = SimpleSrpClient::new(SrpParams::cryptopals(), "alice@example.com", password);
+            let (mut client_end, mut server_end) = MemoryChannel::pair();
+
+            thread::scope(|s| {
+                let server = s.spawn(|| server.serve(&mut server_end));
+                assert_eq!(client.login(&mut client_end).unwrap(), expected);
+                assert_eq!(server.join().unwrap().unwrap(), expected);
+            });
+        }
+    }
+
+    fn crack(password: &str) -> Option<CrackedPassword> {
+        let mallory = DictionaryAttacker::new(SrpParams::cryptopals());
+        let client = SimpleSrpClient::new(SrpParams::cryptopals(), "alice@example.com", password.as_bytes());
+        let (mut client_end, mut mallory_end) = MemoryChannel::pair();
+
+        thread::scope(|s| {
+            let mallory = s.spawn(|| mallory.serve(&mut mallory_end));
+            assert!(!client.login(&mut client_end).unwrap());
+
+            mallory.join().unwrap().expect("attack failed")
+        })
+    }
+
+    #[test]
+    fn example() {
+        let words = WORDS.lines().collect::<Vec<&str>>();
+        let password = words.choose(&mut rand::thread_rng()).unwrap();
+
+        assert_eq!(crack(password), Some(CrackedPassword {
+            email: "alice@example.com".to_string(),
+            password: password.to_string(),
+        }));
+    }
+
+    #[test]
+    fn not_in_dictionary() {
+        assert_eq!(crack("Tr0ub4dor&3"), None);
+    }
+}
@@ -0,0 +1,188 @@
+use eyre::{eyre, Result};
+
+use crate::bigint::BigUint;
+use crate::dh::{derive_key, Group};
+use super::challenge_thirty_four::{
+    decrypt_message, echo_until_hangup, echo_with_key, Channel, Direction, Message, Mitm,
+};
+
+/// Alice's half of the challenge 35 protocol: agree on a group first, then
+/// swap public keys.
+pub fn alice<C: Channel>(chan: &mut C, group: &Group, messages: &[&[u8]]) -> Result<Vec<Vec<u8>>> {
+    chan.send(Message::Negotiate { p: group.p.clone(), g: group.g.clone() })?;
+
+    // Go with whatever Bob says he's using
+    let group = match chan.expect()? {
+        Message::Accept { p, g } => Group::new(p, g),
+        x => return Err(eyre!("expected accept, got {x:?}")),
+    };
+
+    let keys = group.generate_keypair();
+    chan.send(Message::PublicKey(keys.public().clone()))?;
+    let their_public = match chan.expect()? {
+        Message::PublicKey(x) => x,
+        x => return Err(eyre!("expected a public key, got {x:?}")),
+    };
+
+    echo_with_key(chan, &keys.session_key(&their_public), messages)
+}
+
+/// The echo bot again, this time taking whatever group it's offered.
+pub fn echo_bot<C: Channel>(chan: &mut C) -> Result<Vec<Vec<u8>>> {
+    let group = match chan.expect()? {
+        Message::Negotiate { p, g } => Group::new(p, g),
+        x => return Err(eyre!("expected negotiate, got {x:?}")),
+    };
+    chan.send(Message::Accept { p: group.p.clone(), g: group.g.clone() })?;
+
+    let their_public = match chan.expect()? {
+        Message::PublicKey(x) => x,
+        x => return Err(eyre!("expected a public key, got {x:?}")),
+    };
+    let keys = group.generate_keypair();
+    chan.send(Message::PublicKey(keys.public().clone()))?;
+
+    echo_until_hangup(chan, &keys.session_key(&their_public))
+}
+
+/// Generators that leave the shared secret with nowhere to hide.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MaliciousG {
+    /// Every power of 1 is 1
+    One,
+    /// Every power of p is 0 mod p
+    P,
+    /// Every power of -1 is 1 or -1
+    PMinusOne,
+}
+
+impl MaliciousG {
+    pub fn value(self, p: &BigUint) -> BigUint {
+        match self {
+            MaliciousG::One => BigUint::one(),
+            MaliciousG::P => p.clone(),
+            MaliciousG::PMinusOne => p - 1,
+        }
+    }
+}
+
+/// Talks both sides into using a bad generator during negotiation, then
+/// reads the traffic.
+pub struct GeneratorInjection {
+    g: MaliciousG,
+    p: BigUint,
+    publics: Vec<BigUint>,
+    /// Everything either side sent once the keys were agreed
+    pub intercepted: Vec<Vec<u8>>,
+}
+
+impl GeneratorInjection {
+    pub fn new(g: MaliciousG) -> Self {
+        Self { g, p: BigUint::zero(), publics: Vec::new(), intercepted: Vec::new() }
+    }
+
+    /// The secret both sides must have ended up with.
+    pub fn shared_secret(&self) -> BigUint {
+        match self.g {
+            MaliciousG::One => BigUint::one(),
+            MaliciousG::P => BigUint::zero(),
+            // (-1)^(ab) is -1 only when both a and b are odd, in which case
+            // both public keys are -1 too
+            MaliciousG::PMinusOne => {
+                let minus_one = &self.p - 1;
+                if self.publics.iter().all(|x| x == &minus_one) {
+                    minus_one
+                } else {
+                    BigUint::one()
+                }
+            }
+        }
+    }
+}
+
+impl Mitm for GeneratorInjection {
+    fn tamper(&mut self, _: Direction, msg: Message) -> Message {
+        match msg {
+            Message::Negotiate { p, .. } => {
+                self.p = p.clone();
+                Message::Negotiate { g: self.g.value(&p), p }
+            }
+            Message::Accept { p, .. } => Message::Accept { g: self.g.value(&p), p },
+            Message::PublicKey(public) => {
+                self.publics.push(public.clone());
+                Message::PublicKey(public)
+            }
+            msg @ Message::Encrypted { .. } => {
+                if let Ok(plaintext) = decrypt_message(&derive_key(&self.shared_secret()), &msg) {
+                    self.intercepted.push(plaintext);
+                }
+                msg
+            }
+            msg => msg,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::set_five::challenge_thirty_four::{
+        testing::{memory_ends, relayed_session, tcp_ends},
+        Passthrough,
+    };
+
+    const MESSAGES: [&[u8]; 2] = [b"what's the password", b"swordfish"];
+
+    fn session<C, M>(ends: [C; 4], mitm: &mut M) -> (Vec<Vec<u8>>, Vec<Vec<u8>>)
+    where
+        C: Channel + Send,
+        M: Mitm + Send,
+    {
+        relayed_session(ends, mitm, |chan| alice(chan, &Group::cryptopals(), &MESSAGES), echo_bot)
+    }
+
+    #[test]
+    fn honest() {
+        let (echoes, heard) = session(memory_ends(), &mut Passthrough);
+        assert_eq!(echoes, MESSAGES);
+        assert_eq!(heard, MESSAGES);
+
+        let (echoes, _) = session(tcp_ends(), &mut Passthrough);
+        assert_eq!(echoes, MESSAGES);
+    }
+
+    fn attack(g: MaliciousG) {
+        let mut mallory = GeneratorInjection::new(g);
+        let (echoes, heard) = session(memory_ends(), &mut mallory);
+
+        assert_eq!(echoes, MESSAGES, "{g:?}");
+        assert_eq!(heard, MESSAGES, "{g:?}");
+        assert_eq!(mallory.intercepted.len(), MESSAGES.len() * 2, "{g:?}");
+        assert!(mallory.intercepted.iter().zip(MESSAGES.iter().flat_map(|x| [x, x])).all(|(x, y)| x == y));
+    }
+
+    #[test]
+    fn g_is_one() {
+        attack(MaliciousG::One);
+    }
+
+    #[test]
+    fn g_is_p() {
+        attack(MaliciousG::P);
+    }
+
+    #[test]
+    fn g_is_p_minus_one() {
+        // Both cases of the secret come up about half the time each
+        for _ in 0..8 {
+            attack(MaliciousG::PMinusOne);
+        }
+    }
+
+    #[test]
+    fn over_tcp() {
+        let mut mallory = GeneratorInjection::new(MaliciousG::PMinusOne);
+        session(tcp_ends(), &mut mallory);
+        assert_eq!(mallory.intercepted.len(), MESSAGES.len() * 2);
+    }
+}
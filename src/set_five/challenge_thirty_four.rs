@@ -0,0 +1,433 @@
+use std::{
+    io::{self, Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::mpsc::{self, Receiver, Sender},
+};
+
+use eyre::{ensure, eyre, ContextCompat, Result};
+
+use crate::bigint::BigUint;
+use crate::dh::{derive_key, Group};
+use crate::set_two::{
+    challenge_eleven::aes_cbc_enc,
+    challenge_fifteen::strip_pkcs7,
+    challenge_ten::aes_cbc_dec,
+};
+
+/// The biggest frame `TcpChannel` will read. Far more than any message
+/// here needs, and it keeps a hostile length prefix from costing 4 GiB.
+pub const MAX_FRAME_LEN: usize = 1 << 20;
+
+/// Everything Alice, Bob and Mallory say to each other in challenges 34
+/// and 35.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Message {
+    /// Group and public key in one go (challenge 34)
+    Hello { p: BigUint, g: BigUint, public: BigUint },
+    /// Proposes a group (challenge 35)
+    Negotiate { p: BigUint, g: BigUint },
+    /// The group the other side is going to use (challenge 35)
+    Accept { p: BigUint, g: BigUint },
+    PublicKey(BigUint),
+    /// AES-CBC under the session key, with the IV sent alongside
+    Encrypted { iv: Vec<u8>, ciphertext: Vec<u8> },
+}
+
+impl Message {
+    /// A tag byte, then each field as a big endian u32 length and its bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let (tag, fields) = match self {
+            Message::Hello { p, g, public } => (0, vec![p.to_bytes_be(), g.to_bytes_be(), public.to_bytes_be()]),
+            Message::Negotiate { p, g } => (1, vec![p.to_bytes_be(), g.to_bytes_be()]),
+            Message::Accept { p, g } => (2, vec![p.to_bytes_be(), g.to_bytes_be()]),
+            Message::PublicKey(public) => (3, vec![public.to_bytes_be()]),
+            Message::Encrypted { iv, ciphertext } => (4, vec![iv.clone(), ciphertext.clone()]),
+        };
+
+        let mut out = vec![tag];
+        for field in fields {
+            out.extend((field.len() as u32).to_be_bytes());
+            out.extend(field);
+        }
+
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let (tag, mut rest) = bytes.split_first().context("empty message")?;
+
+        let mut fields = Vec::new();
+        while !rest.is_empty() {
+            ensure!(rest.len() >= 4, "truncated field length");
+            let (len, tail) = rest.split_at(4);
+            let len = u32::from_be_bytes(len.try_into().unwrap()) as usize;
+            ensure!(tail.len() >= len, "truncated field");
+
+            fields.push(tail[..len].to_vec());
+            rest = &tail[len..];
+        }
+
+        let int = |i: usize| BigUint::from_bytes_be(&fields[i]);
+        let expected = match tag {
+            0 => 3,
+            1..=2 => 2,
+            3 => 1,
+            4 => 2,
+            _ => return Err(eyre!("unknown message tag {tag}")),
+        };
+        ensure!(fields.len() == expected, "tag {tag} takes {expected} fields, got {}", fields.len());
+
+        Ok(match tag {
+            0 => Message::Hello { p: int(0), g: int(1), public: int(2) },
+            1 => Message::Negotiate { p: int(0), g: int(1) },
+            2 => Message::Accept { p: int(0), g: int(1) },
+            3 => Message::PublicKey(int(0)),
+            _ => Message::Encrypted { iv: fields[0].clone(), ciphertext: fields[1].clone() },
+        })
+    }
+}
+
+/// One end of a two-way link carrying messages of type `M`.
+pub trait Channel<M = Message> {
+    fn send(&mut self, msg: M) -> Result<()>;
+
+    /// The next message, or None once the other end has hung up.
+    fn recv(&mut self) -> Result<Option<M>>;
+
+    /// The next message, which had better be there.
+    fn expect(&mut self) -> Result<M> {
+        self.recv()?.context("other end hung up")
+    }
+}
+
+/// A channel within the process. Carries any message type, since nothing
+/// needs serialising.
+pub struct MemoryChannel<M = Message> {
+    tx: Sender<M>,
+    rx: Receiver<M>,
+}
+
+impl<M> MemoryChannel<M> {
+    /// Two ends wired to each other.
+    pub fn pair() -> (Self, Self) {
+        let (a_tx, b_rx) = mpsc::channel();
+        let (b_tx, a_rx) = mpsc::channel();
+
+        (Self { tx: a_tx, rx: a_rx }, Self { tx: b_tx, rx: b_rx })
+    }
+}
+
+impl<M> Channel<M> for MemoryChannel<M> {
+    fn send(&mut self, msg: M) -> Result<()> {
+        self.tx.send(msg).map_err(|_| eyre!("other end hung up"))
+    }
+
+    fn recv(&mut self) -> Result<Option<M>> {
+        Ok(self.rx.recv().ok())
+    }
+}
+
+/// A channel over TCP, each message framed by a big endian u32 length.
+pub struct TcpChannel {
+    stream: TcpStream,
+}
+
+impl TcpChannel {
+    pub fn new(stream: TcpStream) -> Self {
+        Self { stream }
+    }
+
+    /// Two ends connected over loopback.
+    pub fn pair() -> io::Result<(Self, Self)> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let client = TcpStream::connect(listener.local_addr()?)?;
+        let (server, _) = listener.accept()?;
+
+        Ok((Self::new(client), Self::new(server)))
+    }
+}
+
+impl Channel for TcpChannel {
+    fn send(&mut self, msg: Message) -> Result<()> {
+        let bytes = msg.to_bytes();
+        let mut frame = (bytes.len() as u32).to_be_bytes().to_vec();
+        frame.extend(bytes);
+
+        Ok(self.stream.write_all(&frame)?)
+    }
+
+    fn recv(&mut self) -> Result<Option<Message>> {
+        let mut len = [0u8; 4];
+        match self.stream.read_exact(&mut len) {
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            x => x?,
+        }
+
+        let len = u32::from_be_bytes(len) as usize;
+        ensure!(len <= MAX_FRAME_LEN, "frame of {len} bytes is over the limit");
+
+        let mut bytes = vec![0u8; len];
+        self.stream.read_exact(&mut bytes)?;
+
+        Message::from_bytes(&bytes).map(Some)
+    }
+}
+
+/// Encrypts `msg` under `key` with a fresh IV.
+pub fn encrypt_message(key: &[u8], msg: &[u8]) -> Message {
+    let (iv, ciphertext) = aes_cbc_enc(msg, key);
+    Message::Encrypted { iv, ciphertext }
+}
+
+/// Undoes `encrypt_message`.
+pub fn decrypt_message(key: &[u8], msg: &Message) -> Result<Vec<u8>> {
+    match msg {
+        Message::Encrypted { iv, ciphertext } => {
+            strip_pkcs7(&aes_cbc_dec(ciphertext, key, iv)?)
+        }
+        x => Err(eyre!("expected an encrypted message, got {x:?}")),
+    }
+}
+
+/// Sends each of `messages` to the echo bot under the session key and
+/// checks they come back intact. Returns the echoes.
+pub fn echo_with_key<C: Channel>(chan: &mut C, key: &[u8], messages: &[&[u8]]) -> Result<Vec<Vec<u8>>> {
+    messages
+        .iter()
+        .map(|msg| {
+            chan.send(encrypt_message(key, msg))?;
+            let echo = decrypt_message(key, &chan.expect()?)?;
+            ensure!(&echo == msg, "echo came back as {:?}", String::from_utf8_lossy(&echo));
+
+            Ok(echo)
+        })
+        .collect()
+}
+
+/// Decrypts and re-encrypts everything until the other end hangs up.
+/// Returns what it heard.
+pub fn echo_until_hangup<C: Channel>(chan: &mut C, key: &[u8]) -> Result<Vec<Vec<u8>>> {
+    let mut heard = Vec::new();
+    while let Some(msg) = chan.recv()? {
+        let plaintext = decrypt_message(key, &msg)?;
+        chan.send(encrypt_message(key, &plaintext))?;
+        heard.push(plaintext);
+    }
+
+    Ok(heard)
+}
+
+/// Alice's half of the challenge 34 protocol.
+pub fn alice<C: Channel>(chan: &mut C, group: &Group, messages: &[&[u8]]) -> Result<Vec<Vec<u8>>> {
+    let keys = group.generate_keypair();
+    chan.send(Message::Hello { p: group.p.clone(), g: group.g.clone(), public: keys.public().clone() })?;
+
+    let their_public = match chan.expect()? {
+        Message::PublicKey(x) => x,
+        x => return Err(eyre!("expected a public key, got {x:?}")),
+    };
+
+    echo_with_key(chan, &keys.session_key(&their_public), messages)
+}
+
+/// Bob, who echoes back whatever Alice says under the key they agree on.
+pub fn echo_bot<C: Channel>(chan: &mut C) -> Result<Vec<Vec<u8>>> {
+    let (group, their_public) = match chan.expect()? {
+        Message::Hello { p, g, public } => (Group::new(p, g), public),
+        x => return Err(eyre!("expected hello, got {x:?}")),
+    };
+
+    let keys = group.generate_keypair();
+    chan.send(Message::PublicKey(keys.public().clone()))?;
+
+    echo_until_hangup(chan, &keys.session_key(&their_public))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Direction {
+    AliceToBob,
+    BobToAlice,
+}
+
+/// Sits between Alice and Bob and gets to see and change everything.
+pub trait Mitm {
+    fn tamper(&mut self, direction: Direction, msg: Message) -> Message;
+}
+
+/// Passes messages between Alice and Bob through `mitm` until Alice hangs
+/// up. Both protocols here are strictly call and response.
+pub fn relay<A, B, M>(alice: &mut A, bob: &mut B, mitm: &mut M) -> Result<()>
+where
+    A: Channel,
+    B: Channel,
+    M: Mitm,
+{
+    while let Some(msg) = alice.recv()? {
+        bob.send(mitm.tamper(Direction::AliceToBob, msg))?;
+
+        let reply = bob.expect()?;
+        alice.send(mitm.tamper(Direction::BobToAlice, reply))?;
+    }
+
+    Ok(())
+}
+
+/// Changes nothing and learns nothing.
+pub struct Passthrough;
+
+impl Mitm for Passthrough {
+    fn tamper(&mut self, _: Direction, msg: Message) -> Message {
+        msg
+    }
+}
+
+/// Swaps both public keys for p, so both sides end up with a shared
+/// secret of p^x mod p = 0, and a key Mallory knows.
+#[derive(Default)]
+pub struct ParameterInjection {
+    p: Option<BigUint>,
+    /// Everything either side sent once the keys were agreed
+    pub intercepted: Vec<Vec<u8>>,
+}
+
+impl ParameterInjection {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Mitm for ParameterInjection {
+    fn tamper(&mut self, _: Direction, msg: Message) -> Message {
+        match msg {
+            Message::Hello { p, g, .. } => {
+                self.p = Some(p.clone());
+                Message::Hello { public: p.clone(), p, g }
+            }
+            Message::PublicKey(_) => Message::PublicKey(self.p.clone().unwrap_or_default()),
+            msg @ Message::Encrypted { .. } => {
+                if let Ok(plaintext) = decrypt_message(&derive_key(&BigUint::zero()), &msg) {
+                    self.intercepted.push(plaintext);
+                }
+                msg
+            }
+            msg => msg,
+        }
+    }
+}
+
+/// Harness shared by the MITM tests here and in challenge 35.
+#[cfg(test)]
+pub(crate) mod testing {
+    use std::thread;
+    use super::*;
+
+    /// Alice and Bob talking through Mallory, each in their own thread.
+    /// Returns what Alice got back and what Bob heard.
+    pub(crate) fn relayed_session<C, M, A, B>(ends: [C; 4], mitm: &mut M, alice: A, bob: B) -> (Vec<Vec<u8>>, Vec<Vec<u8>>)
+    where
+        C: Channel + Send,
+        M: Mitm + Send,
+        A: FnOnce(&mut C) -> Result<Vec<Vec<u8>>>,
+        B: FnOnce(&mut C) -> Result<Vec<Vec<u8>>> + Send,
+    {
+        let [mut alice_end, mut mallory_alice, mut mallory_bob, mut bob_end] = ends;
+
+        thread::scope(|s| {
+            let bob = s.spawn(move || bob(&mut bob_end));
+            // Mallory's ends drop once Alice hangs up, which lets Bob finish
+            let mallory = s.spawn(move || relay(&mut mallory_alice, &mut mallory_bob, mitm));
+
+            let echoes = alice(&mut alice_end).expect("alice failed");
+            drop(alice_end);
+
+            mallory.join().unwrap().expect("mallory failed");
+            (echoes, bob.join().unwrap().expect("bob failed"))
+        })
+    }
+
+    /// Alice to Mallory and Mallory to Bob, in memory.
+    pub(crate) fn memory_ends() -> [MemoryChannel; 4] {
+        let (a, b) = MemoryChannel::pair();
+        let (c, d) = MemoryChannel::pair();
+        [a, b, c, d]
+    }
+
+    /// The same over loopback TCP.
+    pub(crate) fn tcp_ends() -> [TcpChannel; 4] {
+        let (a, b) = TcpChannel::pair().unwrap();
+        let (c, d) = TcpChannel::pair().unwrap();
+        [a, b, c, d]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::testing::{memory_ends, relayed_session, tcp_ends};
+
+    const MESSAGES: [&[u8]; 3] = [b"hello bob", b"it's alice", b"this is between the two of us"];
+
+    fn session<C, M>(ends: [C; 4], mitm: &mut M) -> (Vec<Vec<u8>>, Vec<Vec<u8>>)
+    where
+        C: Channel + Send,
+        M: Mitm + Send,
+    {
+        relayed_session(ends, mitm, |chan| alice(chan, &Group::cryptopals(), &MESSAGES), echo_bot)
+    }
+
+    #[test]
+    fn wire_format() {
+        let messages = [
+            Message::Hello { p: BigUint::from(37u64), g: BigUint::from(5u64), public: BigUint::zero() },
+            Message::Accept { p: BigUint::from(37u64), g: BigUint::one() },
+            Message::PublicKey(Group::cryptopals().p),
+            Message::Encrypted { iv: vec![1; 16], ciphertext: vec![] },
+        ];
+        for msg in messages {
+            assert_eq!(Message::from_bytes(&msg.to_bytes()).unwrap(), msg);
+        }
+
+        assert!(Message::from_bytes(&[9]).is_err());
+        assert!(Message::from_bytes(&[3, 0, 0, 0, 5, 1]).is_err());
+    }
+
+    #[test]
+    fn oversized_frame() {
+        let (mut a, mut b) = TcpChannel::pair().unwrap();
+        a.stream.write_all(&u32::MAX.to_be_bytes()).unwrap();
+        assert!(b.recv().is_err());
+
+        // Right up to the limit is fine
+        let (mut a, mut b) = TcpChannel::pair().unwrap();
+        let msg = Message::Encrypted { iv: vec![0; 16], ciphertext: vec![0; MAX_FRAME_LEN - 64] };
+        a.send(msg.clone()).unwrap();
+        assert_eq!(b.recv().unwrap(), Some(msg));
+    }
+
+    #[test]
+    fn honest() {
+        let (echoes, heard) = session(memory_ends(), &mut Passthrough);
+        assert_eq!(echoes, MESSAGES);
+        assert_eq!(heard, MESSAGES);
+
+        let (echoes, _) = session(tcp_ends(), &mut Passthrough);
+        assert_eq!(echoes, MESSAGES);
+    }
+
+    #[test]
+    fn example() {
+        let mut mallory = ParameterInjection::new();
+        let (echoes, heard) = session(memory_ends(), &mut mallory);
+
+        // Alice and Bob are none the wiser...
+        assert_eq!(echoes, MESSAGES);
+        assert_eq!(heard, MESSAGES);
+        // ...but Mallory read both directions
+        assert_eq!(mallory.intercepted.len(), MESSAGES.len() * 2);
+        assert!(mallory.intercepted.iter().zip(MESSAGES.iter().flat_map(|x| [x, x])).all(|(x, y)| x == y));
+
+        let mut mallory = ParameterInjection::new();
+        session(tcp_ends(), &mut mallory);
+        assert_eq!(mallory.intercepted.len(), MESSAGES.len() * 2);
+    }
+}
@@ -0,0 +1,89 @@
+use eyre::{eyre, Result};
+
+use crate::bigint::BigUint;
+use super::challenge_thirty_four::Channel;
+use super::challenge_thirty_six::{proof, session_key, SrpMessage};
+
+/// What a login attempt got us.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LoginOutcome {
+    /// In, sharing this session key with the server
+    LoggedIn { key: [u8; 32] },
+    Refused,
+}
+
+/// Logs in as `email` without knowing the password by sending
+/// A = `multiple` * N. The server's S = (A * v^u)^b is then 0 mod N
+/// whatever v is, so K = H(0) and the proof is easy.
+pub fn zero_key_login<C: Channel<SrpMessage>>(
+    chan: &mut C,
+    email: &str,
+    n: &BigUint,
+    multiple: u64,
+) -> Result<LoginOutcome>
+{
+    chan.send(SrpMessage::Hello { email: email.to_string(), public: n * multiple })?;
+
+    let salt = match chan.expect()? {
+        SrpMessage::Challenge { salt, .. } => salt,
+        SrpMessage::Verdict(_) => return Ok(LoginOutcome::Refused),
+        x => return Err(eyre!("expected challenge, got {x:?}")),
+    };
+
+    let key = session_key(&BigUint::zero());
+    chan.send(SrpMessage::Proof(proof(&key, &salt)))?;
+
+    match chan.expect()? {
+        SrpMessage::Verdict(true) => Ok(LoginOutcome::LoggedIn { key }),
+        SrpMessage::Verdict(false) => Ok(LoginOutcome::Refused),
+        x => Err(eyre!("expected verdict, got {x:?}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+    use super::*;
+    use crate::set_five::{
+        challenge_thirty_four::MemoryChannel,
+        challenge_thirty_six::{SrpParams, SrpServer},
+    };
+
+    fn attempt(server: &SrpServer, multiple: u64) -> LoginOutcome {
+        let (mut attacker, mut server_end) = MemoryChannel::pair();
+
+        thread::scope(|s| {
+            let handle = s.spawn(move || server.serve(&mut server_end));
+            let outcome = zero_key_login(&mut attacker, "alice@example.com", &server.params().n, multiple)
+                .expect("attack failed");
+
+            // The server agrees about whether we got in
+            let served = handle.join().unwrap().expect("server failed");
+            assert_eq!(served, matches!(outcome, LoginOutcome::LoggedIn { .. }));
+            outcome
+        })
+    }
+
+    #[test]
+    fn example() {
+        let mut server = SrpServer::naive(SrpParams::cryptopals());
+        server.register("alice@example.com", b"correct horse battery staple");
+
+        for multiple in 0..4 {
+            assert_eq!(
+                attempt(&server, multiple),
+                LoginOutcome::LoggedIn { key: session_key(&BigUint::zero()) },
+                "A = {multiple}N");
+        }
+    }
+
+    #[test]
+    fn checked_server() {
+        let mut server = SrpServer::new(SrpParams::cryptopals());
+        server.register("alice@example.com", b"correct horse battery staple");
+
+        for multiple in 0..4 {
+            assert_eq!(attempt(&server, multiple), LoginOutcome::Refused);
+        }
+    }
+}
@@ -0,0 +1,377 @@
+//! SHA-256, per FIPS 180-4, and SRP-6a on top of it.
+use std::collections::HashMap;
+
+use eyre::{ensure, eyre, Result};
+use rand::Rng;
+
+use crate::bigint::BigUint;
+use crate::dh::Group;
+use crate::set_four::{
+    challenge_thirty::{Buffered, MerkleDamgard},
+    challenge_thirty_one::hmac,
+    challenge_twenty_eight::padding,
+};
+use super::challenge_thirty_four::Channel;
+
+const INITIAL_STATE: [u32; 8] = [
+    0x6A09_E667, 0xBB67_AE85, 0x3C6E_F372, 0xA54F_F53A, 0x510E_527F, 0x9B05_688C, 0x1F83_D9AB, 0x5BE0_CD19,
+];
+
+const K: [u32; 64] = [
+    0x428A_2F98, 0x7137_4491, 0xB5C0_FBCF, 0xE9B5_DBA5,
+    0x3956_C25B, 0x59F1_11F1, 0x923F_82A4, 0xAB1C_5ED5,
+    0xD807_AA98, 0x1283_5B01, 0x2431_85BE, 0x550C_7DC3,
+    0x72BE_5D74, 0x80DE_B1FE, 0x9BDC_06A7, 0xC19B_F174,
+    0xE49B_69C1, 0xEFBE_4786, 0x0FC1_9DC6, 0x240C_A1CC,
+    0x2DE9_2C6F, 0x4A74_84AA, 0x5CB0_A9DC, 0x76F9_88DA,
+    0x983E_5152, 0xA831_C66D, 0xB003_27C8, 0xBF59_7FC7,
+    0xC6E0_0BF3, 0xD5A7_9147, 0x06CA_6351, 0x1429_2967,
+    0x27B7_0A85, 0x2E1B_2138, 0x4D2C_6DFC, 0x5338_0D13,
+    0x650A_7354, 0x766A_0ABB, 0x81C2_C92E, 0x9272_2C85,
+    0xA2BF_E8A1, 0xA81A_664B, 0xC24B_8B70, 0xC76C_51A3,
+    0xD192_E819, 0xD699_0624, 0xF40E_3585, 0x106A_A070,
+    0x19A4_C116, 0x1E37_6C08, 0x2748_774C, 0x34B0_BCB5,
+    0x391C_0CB3, 0x4ED8_AA4A, 0x5B9C_CA4F, 0x682E_6FF3,
+    0x748F_82EE, 0x78A5_636F, 0x84C8_7814, 0x8CC7_0208,
+    0x90BE_FFFA, 0xA450_6CEB, 0xBEF9_A3F7, 0xC671_78F2,
+];
+
+#[derive(Clone)]
+pub struct Sha256(pub Buffered<[u32; 8]>);
+
+impl MerkleDamgard for Sha256 {
+    type State = [u32; 8];
+    type Digest = [u8; 32];
+
+    const INITIAL_STATE: [u32; 8] = INITIAL_STATE;
+
+    fn from_buffered(buffered: Buffered<[u32; 8]>) -> Self {
+        Self(buffered)
+    }
+
+    fn buffered(&mut self) -> &mut Buffered<[u32; 8]> {
+        &mut self.0
+    }
+
+    fn compress(state: &mut [u32; 8], block: &[u8]) {
+        compress(state, block)
+    }
+
+    /// Same as SHA-1's.
+    fn padding(len: u64) -> Vec<u8> {
+        padding(len)
+    }
+
+    fn digest_from_state(state: &[u32; 8]) -> [u8; 32] {
+        let mut digest = [0u8; 32];
+        for (bytes, word) in digest.chunks_mut(4).zip(state) {
+            bytes.copy_from_slice(&word.to_be_bytes());
+        }
+
+        digest
+    }
+
+    fn state_from_digest(digest: &[u8; 32]) -> [u32; 8] {
+        let mut state = [0u32; 8];
+        for (word, bytes) in state.iter_mut().zip(digest.chunks(4)) {
+            *word = u32::from_be_bytes(bytes.try_into().unwrap());
+        }
+
+        state
+    }
+}
+
+impl Default for Sha256 {
+    fn default() -> Self {
+        <Self as MerkleDamgard>::new()
+    }
+}
+
+fn compress(state: &mut [u32; 8], block: &[u8]) {
+    let mut w = [0u32; 64];
+    for (word, bytes) in w.iter_mut().zip(block.chunks(4)) {
+        *word = u32::from_be_bytes(bytes.try_into().unwrap());
+    }
+    for i in 16..64 {
+        let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+        let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+        w[i] = w[i - 16].wrapping_add(s0).wrapping_add(w[i - 7]).wrapping_add(s1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+    for (word, k) in w.iter().zip(K) {
+        let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let ch = (e & f) ^ (!e & g);
+        let temp1 = h.wrapping_add(s1).wrapping_add(ch).wrapping_add(k).wrapping_add(*word);
+        let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let temp2 = s0.wrapping_add(maj);
+
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(temp1);
+        d = c;
+        c = b;
+        b = a;
+        a = temp1.wrapping_add(temp2);
+    }
+
+    for (word, x) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+        *word = word.wrapping_add(x);
+    }
+}
+
+/// N, g and the multiplier k that both sides agree on up front.
+#[derive(Clone, Debug)]
+pub struct SrpParams {
+    pub n: BigUint,
+    pub g: BigUint,
+    pub k: BigUint,
+}
+
+impl SrpParams {
+    /// The challenge's choice: the NIST prime, g = 2 and k = 3.
+    pub fn cryptopals() -> Self {
+        let Group { p, g } = Group::cryptopals();
+        Self { n: p, g, k: BigUint::from(3u64) }
+    }
+}
+
+/// What goes over the wire during a login.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SrpMessage {
+    /// C -> S: I, A
+    Hello { email: String, public: BigUint },
+    /// S -> C: salt, B
+    Challenge { salt: Vec<u8>, public: BigUint },
+    /// C -> S: HMAC-SHA256(K, salt)
+    Proof([u8; 32]),
+    /// S -> C: whether the proof checked out
+    Verdict(bool),
+}
+
+/// SHA-256 of the concatenation, as a number.
+pub fn hash_to_int(parts: &[&[u8]]) -> BigUint {
+    BigUint::from_bytes_be(&Sha256::digest(&parts.concat()))
+}
+
+/// u = H(A || B)
+pub fn scrambler(client_public: &BigUint, server_public: &BigUint) -> BigUint {
+    hash_to_int(&[&client_public.to_bytes_be(), &server_public.to_bytes_be()])
+}
+
+/// x = H(salt || password)
+pub fn password_exponent(salt: &[u8], password: &[u8]) -> BigUint {
+    hash_to_int(&[salt, password])
+}
+
+/// K = H(S)
+pub fn session_key(secret: &BigUint) -> [u8; 32] {
+    Sha256::digest(&secret.to_bytes_be())
+}
+
+/// The client's proof that it has K: HMAC-SHA256(K, salt).
+pub fn proof(key: &[u8; 32], salt: &[u8]) -> [u8; 32] {
+    hmac::<Sha256>(key, salt)
+}
+
+// All the server keeps per user: never the password itself.
+struct Verifier {
+    salt: Vec<u8>,
+    // g^x mod N
+    v: BigUint,
+}
+
+pub struct SrpServer {
+    params: SrpParams,
+    users: HashMap<String, Verifier>,
+    // SRP-6a says to abort if A = 0 mod N. The challenge's server doesn't.
+    check_public: bool,
+}
+
+impl SrpServer {
+    pub fn new(params: SrpParams) -> Self {
+        Self { params, users: HashMap::new(), check_public: true }
+    }
+
+    /// A server that trusts whatever A it's given, like the one in
+    /// challenge 37.
+    pub fn naive(params: SrpParams) -> Self {
+        Self { check_public: false, ..Self::new(params) }
+    }
+
+    pub fn params(&self) -> &SrpParams {
+        &self.params
+    }
+
+    pub fn register(&mut self, email: &str, password: &[u8]) {
+        let salt = rand::thread_rng().gen::<[u8; 16]>().to_vec();
+        let x = password_exponent(&salt, password);
+        let v = self.params.g.modpow(&x, &self.params.n);
+
+        self.users.insert(email.to_string(), Verifier { salt, v });
+    }
+
+    /// Answers a client's hello with the salt and B, and works out the key
+    /// the client had better prove it has.
+    pub fn handshake(&self, email: &str, client_public: &BigUint) -> Result<(ServerSession, SrpMessage)> {
+        let SrpParams { n, g, k } = &self.params;
+        let user = self.users.get(email).ok_or_else(|| eyre!("no such user {email:?}"))?;
+        if self.check_public {
+            ensure!(!(client_public % n).is_zero(), "client public key is 0 mod N");
+        }
+
+        let b = BigUint::random_below(n, &mut rand::thread_rng());
+        let server_public = (k * &user.v + g.modpow(&b, n)) % n;
+
+        // S = (A * v^u)^b mod N
+        let u = scrambler(client_public, &server_public);
+        let secret = (client_public * user.v.modpow(&u, n) % n).modpow(&b, n);
+
+        let session = ServerSession { salt: user.salt.clone(), key: session_key(&secret) };
+        let reply = SrpMessage::Challenge { salt: user.salt.clone(), public: server_public };
+        Ok((session, reply))
+    }
+
+    /// Handles one login over `chan`. Returns whether the client got in.
+    pub fn serve<C: Channel<SrpMessage>>(&self, chan: &mut C) -> Result<bool> {
+        let (email, client_public) = match chan.expect()? {
+            SrpMessage::Hello { email, public } => (email, public),
+            x => return Err(eyre!("expected hello, got {x:?}")),
+        };
+
+        // Unknown users and bad keys get the same answer as bad passwords
+        let Ok((session, reply)) = self.handshake(&email, &client_public) else {
+            chan.send(SrpMessage::Verdict(false))?;
+            return Ok(false);
+        };
+        chan.send(reply)?;
+
+        let valid = match chan.expect()? {
+            SrpMessage::Proof(proof) => session.verify(&proof),
+            x => return Err(eyre!("expected proof, got {x:?}")),
+        };
+        chan.send(SrpMessage::Verdict(valid))?;
+
+        Ok(valid)
+    }
+}
+
+/// The server's half of a login in progress.
+pub struct ServerSession {
+    salt: Vec<u8>,
+    key: [u8; 32],
+}
+
+impl ServerSession {
+    pub fn verify(&self, client_proof: &[u8; 32]) -> bool {
+        &proof(&self.key, &self.salt) == client_proof
+    }
+}
+
+pub struct SrpClient {
+    params: SrpParams,
+    email: String,
+    password: Vec<u8>,
+}
+
+impl SrpClient {
+    pub fn new(params: SrpParams, email: &str, password: &[u8]) -> Self {
+        Self { params, email: email.to_string(), password: password.to_vec() }
+    }
+
+    /// Logs in over `chan`. Returns whether the server let us in.
+    pub fn login<C: Channel<SrpMessage>>(&self, chan: &mut C) -> Result<bool> {
+        let SrpParams { n, g, k } = &self.params;
+
+        let a = BigUint::random_below(n, &mut rand::thread_rng());
+        let client_public = g.modpow(&a, n);
+        chan.send(SrpMessage::Hello { email: self.email.clone(), public: client_public.clone() })?;
+
+        let (salt, server_public) = match chan.expect()? {
+            SrpMessage::Challenge { salt, public } => (salt, public),
+            SrpMessage::Verdict(x) => return Ok(x),
+            x => return Err(eyre!("expected challenge, got {x:?}")),
+        };
+        ensure!(!(&server_public % n).is_zero(), "server public key is 0 mod N");
+
+        // S = (B - k * g^x)^(a + u * x) mod N, adding a multiple of N first
+        // so the subtraction can't go negative
+        let u = scrambler(&client_public, &server_public);
+        let x = password_exponent(&salt, &self.password);
+        let base = (&server_public % n + k * n - k * g.modpow(&x, n)) % n;
+        let secret = base.modpow(&(a + u * x), n);
+
+        chan.send(SrpMessage::Proof(proof(&session_key(&secret), &salt)))?;
+        match chan.expect()? {
+            SrpMessage::Verdict(x) => Ok(x),
+            x => Err(eyre!("expected verdict, got {x:?}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+    use super::*;
+    use crate::set_one::challenge_one::decode_hex;
+    use crate::set_five::challenge_thirty_four::MemoryChannel;
+
+    fn check(input: &[u8], expected: &str) {
+        let expected = decode_hex(expected.as_bytes()).unwrap();
+        assert_eq!(Sha256::digest(input).to_vec(), expected, "{:?}", String::from_utf8_lossy(input));
+    }
+
+    #[test]
+    fn fips_vectors() {
+        check(b"abc", "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        check(b"", "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+        check(
+            b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1");
+
+        let mut sha256 = Sha256::new();
+        for _ in 0..1000 {
+            sha256.update(&[b'a'; 1000]);
+        }
+        let expected = decode_hex(b"cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0").unwrap();
+        assert_eq!(sha256.finalize().to_vec(), expected);
+    }
+
+    #[test]
+    fn hmac_vectors() {
+        // RFC 4231
+        let mac = hmac::<Sha256>(&[0x0B; 20], b"Hi There");
+        assert_eq!(mac.to_vec(), decode_hex(b"b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7").unwrap());
+
+        let mac = hmac::<Sha256>(&[0xAA; 131], b"Test Using Larger Than Block-Size Key - Hash Key First");
+        assert_eq!(mac.to_vec(), decode_hex(b"60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54").unwrap());
+    }
+
+    fn login(server: &SrpServer, client: &SrpClient) -> (bool, bool) {
+        let (mut client_end, mut server_end) = MemoryChannel::pair();
+
+        thread::scope(|s| {
+            let server = s.spawn(move || server.serve(&mut server_end));
+            let client = client.login(&mut client_end).expect("client failed");
+
+            (client, server.join().unwrap().expect("server failed"))
+        })
+    }
+
+    #[test]
+    fn example() {
+        let mut server = SrpServer::new(SrpParams::cryptopals());
+        server.register("alice@example.com", b"hunter2");
+
+        let client = SrpClient::new(SrpParams::cryptopals(), "alice@example.com", b"hunter2");
+        assert_eq!(login(&server, &client), (true, true));
+
+        let client = SrpClient::new(SrpParams::cryptopals(), "alice@example.com", b"hunter3");
+        assert_eq!(login(&server, &client), (false, false));
+
+        let client = SrpClient::new(SrpParams::cryptopals(), "bob@example.com", b"hunter2");
+        assert_eq!(login(&server, &client), (false, false));
+    }
+}
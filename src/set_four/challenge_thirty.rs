@@ -0,0 +1,279 @@
+//! MD4, per RFC 1320, and length extension for any Merkle–Damgård hash.
+use std::fmt::Debug;
+
+/// What every Merkle–Damgård hash carries between updates, whatever its
+/// compression function.
+#[derive(Clone)]
+pub struct Buffered<S> {
+    pub state: S,
+    // Bytes that don't make up a full block yet
+    buffer: Vec<u8>,
+    // Total bytes hashed so far, including any before `from_state`
+    len: u64,
+}
+
+/// A Merkle–Damgård hash whose internal state can be read back out of a
+/// digest and resumed from, which is everything length extension needs.
+///
+/// A hash only brings its compression function, padding and digest format;
+/// buffering and finalizing are the same for all of them.
+pub trait MerkleDamgard: Sized {
+    type State;
+    type Digest: AsRef<[u8]> + Copy + PartialEq + Debug;
+
+    /// Bytes per compression function input
+    const BLOCK_SIZE: usize = 64;
+
+    const INITIAL_STATE: Self::State;
+
+    fn from_buffered(buffered: Buffered<Self::State>) -> Self;
+
+    fn buffered(&mut self) -> &mut Buffered<Self::State>;
+
+    /// Mixes one block into the state.
+    fn compress(state: &mut Self::State, block: &[u8]);
+
+    /// The padding appended to a message of `len` bytes.
+    fn padding(len: u64) -> Vec<u8>;
+
+    /// Serialises the final state.
+    fn digest_from_state(state: &Self::State) -> Self::Digest;
+
+    /// The internal state a digest leaves behind.
+    fn state_from_digest(digest: &Self::Digest) -> Self::State;
+
+    fn new() -> Self {
+        Self::from_state(Self::INITIAL_STATE, 0)
+    }
+
+    /// Picks up hashing from an arbitrary internal state, as if `len` bytes
+    /// (a multiple of the block size) had already been processed.
+    fn from_state(state: Self::State, len: u64) -> Self {
+        Self::from_buffered(Buffered { state, buffer: Vec::with_capacity(Self::BLOCK_SIZE), len })
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        let Buffered { state, buffer, len } = self.buffered();
+        *len += data.len() as u64;
+        buffer.extend(data);
+
+        let full_blocks = buffer.len() / Self::BLOCK_SIZE * Self::BLOCK_SIZE;
+        for block in buffer[..full_blocks].chunks(Self::BLOCK_SIZE) {
+            Self::compress(state, block);
+        }
+        buffer.drain(..full_blocks);
+    }
+
+    fn finalize(mut self) -> Self::Digest {
+        let len = self.buffered().len;
+        self.update(&Self::padding(len));
+
+        Self::digest_from_state(&self.buffered().state)
+    }
+
+    fn digest(data: &[u8]) -> Self::Digest {
+        let mut hash = Self::new();
+        hash.update(data);
+        hash.finalize()
+    }
+}
+
+/// MAC(key, message) = H(key || message).
+pub fn prefix_mac<H: MerkleDamgard>(key: &[u8], msg: &[u8]) -> H::Digest {
+    let mut hash = H::new();
+    hash.update(key);
+    hash.update(msg);
+    hash.finalize()
+}
+
+/// Given MAC(key || msg), forges a MAC for
+/// msg || glue padding || extension, assuming the key is `key_len` bytes.
+/// Returns the forged message (sans key) and its MAC.
+pub fn forge_mac<H: MerkleDamgard>(
+    mac: &H::Digest,
+    msg: &[u8],
+    key_len: usize,
+    extension: &[u8],
+) -> (Vec<u8>, H::Digest)
+{
+    let glue = H::padding((key_len + msg.len()) as u64);
+    let processed = key_len + msg.len() + glue.len();
+
+    let mut hash = H::from_state(H::state_from_digest(mac), processed as u64);
+    hash.update(extension);
+
+    let mut forged = msg.to_vec();
+    forged.extend(glue);
+    forged.extend(extension);
+
+    (forged, hash.finalize())
+}
+
+/// Forges a MAC for msg || glue padding || extension by trying every key
+/// length up to `max_key_len` until `verify` accepts one.
+pub fn length_extension_attack<H, F>(
+    mac: &H::Digest,
+    msg: &[u8],
+    extension: &[u8],
+    max_key_len: usize,
+    verify: F,
+) -> Option<(Vec<u8>, H::Digest)>
+where
+    H: MerkleDamgard,
+    F: Fn(&[u8], &H::Digest) -> bool,
+{
+    (0..=max_key_len)
+        .map(|key_len| forge_mac::<H>(mac, msg, key_len, extension))
+        .find(|(forged, forged_mac)| verify(forged, forged_mac))
+}
+
+const INITIAL_STATE: [u32; 4] = [0x6745_2301, 0xEFCD_AB89, 0x98BA_DCFE, 0x1032_5476];
+
+#[derive(Clone)]
+pub struct Md4(pub Buffered<[u32; 4]>);
+
+impl MerkleDamgard for Md4 {
+    type State = [u32; 4];
+    type Digest = [u8; 16];
+
+    const INITIAL_STATE: [u32; 4] = INITIAL_STATE;
+
+    fn from_buffered(buffered: Buffered<[u32; 4]>) -> Self {
+        Self(buffered)
+    }
+
+    fn buffered(&mut self) -> &mut Buffered<[u32; 4]> {
+        &mut self.0
+    }
+
+    fn compress(state: &mut [u32; 4], block: &[u8]) {
+        compress(state, block)
+    }
+
+    fn digest_from_state(state: &[u32; 4]) -> [u8; 16] {
+        let mut digest = [0u8; 16];
+        for (bytes, word) in digest.chunks_mut(4).zip(state) {
+            bytes.copy_from_slice(&word.to_le_bytes());
+        }
+
+        digest
+    }
+
+    fn state_from_digest(digest: &[u8; 16]) -> [u32; 4] {
+        let mut state = [0u32; 4];
+        for (word, bytes) in state.iter_mut().zip(digest.chunks(4)) {
+            *word = u32::from_le_bytes(bytes.try_into().unwrap());
+        }
+
+        state
+    }
+
+    /// Same as SHA-1's, except the length is little endian.
+    fn padding(len: u64) -> Vec<u8> {
+        let mut padding = vec![0x80];
+        padding.extend(std::iter::repeat_n(0, (119 - len as usize % 64) % 64));
+        padding.extend((len * 8).to_le_bytes());
+
+        padding
+    }
+}
+
+impl Default for Md4 {
+    fn default() -> Self {
+        <Self as MerkleDamgard>::new()
+    }
+}
+
+fn compress(state: &mut [u32; 4], block: &[u8]) {
+    let mut x = [0u32; 16];
+    for (word, bytes) in x.iter_mut().zip(block.chunks(4)) {
+        *word = u32::from_le_bytes(bytes.try_into().unwrap());
+    }
+
+    let f = |x: u32, y: u32, z: u32| (x & y) | (!x & z);
+    let g = |x: u32, y: u32, z: u32| (x & y) | (x & z) | (y & z);
+    let h = |x: u32, y: u32, z: u32| x ^ y ^ z;
+
+    type Round<'a> = (&'a dyn Fn(u32, u32, u32) -> u32, u32, [usize; 16], [u32; 4]);
+    let rounds: [Round; 3] = [
+        (&f, 0, [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15], [3, 7, 11, 19]),
+        (&g, 0x5A82_7999, [0, 4, 8, 12, 1, 5, 9, 13, 2, 6, 10, 14, 3, 7, 11, 15], [3, 5, 9, 13]),
+        (&h, 0x6ED9_EBA1, [0, 8, 4, 12, 2, 10, 6, 14, 1, 9, 5, 13, 3, 11, 7, 15], [3, 9, 11, 15]),
+    ];
+
+    // Each step updates a, d, c, b in turn, mixing in the other three in
+    // order after it.
+    let mut v = *state;
+    for (func, k, order, shifts) in rounds {
+        for (i, idx) in order.iter().enumerate() {
+            let j = (4 - i % 4) % 4;
+            v[j] = v[j]
+                .wrapping_add(func(v[(j + 1) % 4], v[(j + 2) % 4], v[(j + 3) % 4]))
+                .wrapping_add(x[*idx])
+                .wrapping_add(k)
+                .rotate_left(shifts[i % 4]);
+        }
+    }
+
+    for (word, x) in state.iter_mut().zip(v) {
+        *word = word.wrapping_add(x);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::set_one::challenge_one::decode_hex;
+    use crate::set_four::challenge_twenty_eight::Sha1;
+
+    fn check(input: &[u8], expected: &str) {
+        let expected = decode_hex(expected.as_bytes()).unwrap();
+        assert_eq!(Md4::digest(input).to_vec(), expected, "{:?}", String::from_utf8_lossy(input));
+    }
+
+    #[test]
+    fn rfc_vectors() {
+        check(b"", "31d6cfe0d16ae931b73c59d7e0c089c0");
+        check(b"a", "bde52cb31de33e46245e05fbdbd6fb24");
+        check(b"abc", "a448017aaf21d8525fc10ae87aa6729d");
+        check(b"message digest", "d9130a8164549fe818874806e1c7014b");
+        check(b"abcdefghijklmnopqrstuvwxyz", "d79e1c308aa5bbcdeea8ed63df412da9");
+        check(
+            b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789",
+            "043f8582f241db351ce627e153e7f0e4");
+        check(
+            b"12345678901234567890123456789012345678901234567890123456789012345678901234567890",
+            "e33b4ddc9c38f2199c3e7b164fcc0536");
+    }
+
+    #[test]
+    fn streaming() {
+        let msg = b"12345678901234567890123456789012345678901234567890123456789012345678901234567890";
+
+        let mut md4 = Md4::new();
+        for chunk in msg.chunks(7) {
+            md4.update(chunk);
+        }
+
+        assert_eq!(md4.finalize(), Md4::digest(msg));
+    }
+
+    fn attack<H: MerkleDamgard>() {
+        let key = b"YELLOW SUBMARINE";
+        let msg = b"comment1=cooking%20MCs;userdata=foo;comment2=%20like%20a%20pound%20of%20bacon";
+        let mac = prefix_mac::<H>(key, msg);
+
+        let (forged, forged_mac) = length_extension_attack::<H, _>(&mac, msg, b";admin=true", 32, |msg, mac| {
+            &prefix_mac::<H>(key, msg) == mac
+        }).expect("no key length worked");
+
+        assert!(forged.ends_with(b";admin=true"));
+        assert_eq!(prefix_mac::<H>(key, &forged), forged_mac);
+    }
+
+    #[test]
+    fn example() {
+        attack::<Md4>();
+        attack::<Sha1>();
+    }
+}
@@ -0,0 +1,237 @@
+use std::{
+    fmt::Write as _,
+    io::{self, BufRead, BufReader, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::{atomic::{AtomicBool, Ordering}, Arc},
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use crate::set_one::challenge_one::decode_hex;
+use super::challenge_thirty::MerkleDamgard;
+
+/// HMAC (RFC 2104) over any of the crate's hashes.
+pub fn hmac<H: MerkleDamgard>(key: &[u8], msg: &[u8]) -> H::Digest {
+    let mut key = if key.len() > H::BLOCK_SIZE {
+        H::digest(key).as_ref().to_vec()
+    } else {
+        key.to_vec()
+    };
+    key.resize(H::BLOCK_SIZE, 0);
+
+    let mut inner = H::new();
+    inner.update(&key.iter().map(|x| x ^ 0x36).collect::<Vec<u8>>());
+    inner.update(msg);
+    let inner = inner.finalize();
+
+    let mut outer = H::new();
+    outer.update(&key.iter().map(|x| x ^ 0x5C).collect::<Vec<u8>>());
+    outer.update(inner.as_ref());
+    outer.finalize()
+}
+
+/// Compares byte by byte, bailing at the first difference and sleeping
+/// `delay` after every byte that matches.
+pub fn insecure_compare(lhs: &[u8], rhs: &[u8], delay: Duration) -> bool {
+    if lhs.len() != rhs.len() {
+        return false;
+    }
+
+    for (x, y) in lhs.iter().zip(rhs) {
+        if x != y {
+            return false;
+        }
+        thread::sleep(delay);
+    }
+
+    true
+}
+
+/// Lowercase, zero-padded hex, the way signatures go over the wire.
+pub fn to_hex(input: &[u8]) -> String {
+    input.iter().fold(String::new(), |mut out, b| {
+        let _ = write!(out, "{b:02x}");
+        out
+    })
+}
+
+/// A tiny HTTP server on 127.0.0.1 that answers
+/// `GET /test?file=...&signature=...` with 200 if the signature is the
+/// HMAC of the file name under its key, and 500 otherwise. The signature
+/// check is `insecure_compare`.
+///
+/// Each connection gets its own thread and can carry any number of
+/// requests. The server stops accepting connections when dropped.
+pub struct HmacServer {
+    addr: SocketAddr,
+    shutdown: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl HmacServer {
+    pub fn spawn<H>(key: Vec<u8>, delay: Duration) -> io::Result<Self>
+    where
+        H: MerkleDamgard + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+        let shutdown = Arc::new(AtomicBool::new(false));
+
+        let stop = shutdown.clone();
+        let handle = thread::spawn(move || {
+            for stream in listener.incoming() {
+                if stop.load(Ordering::SeqCst) {
+                    break;
+                }
+
+                if let Ok(stream) = stream {
+                    let key = key.clone();
+                    // A misbehaving client is its own problem
+                    thread::spawn(move || handle_connection::<H>(stream, &key, delay));
+                }
+            }
+        });
+
+        Ok(Self { addr, shutdown, handle: Some(handle) })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+}
+
+impl Drop for HmacServer {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+        // Wake the accept loop up so it notices
+        let _ = TcpStream::connect(self.addr);
+
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+// Serves requests off one connection until the client hangs up.
+fn handle_connection<H: MerkleDamgard>(stream: TcpStream, key: &[u8], delay: Duration) -> io::Result<()> {
+    let mut reader = BufReader::new(&stream);
+    let mut writer = &stream;
+
+    loop {
+        let mut request_line = String::new();
+        if reader.read_line(&mut request_line)? == 0 {
+            return Ok(());
+        }
+
+        // Skip the headers, there's nothing in them we care about
+        let mut header = String::new();
+        while reader.read_line(&mut header)? > 2 {
+            header.clear();
+        }
+
+        let valid = parse_query(&request_line)
+            .map(|(file, signature)| {
+                let expected = hmac::<H>(key, file.as_bytes());
+                insecure_compare(&signature, expected.as_ref(), delay)
+            })
+            .unwrap_or(false);
+
+        let status = if valid { "200 OK" } else { "500 Internal Server Error" };
+        let response = format!("HTTP/1.1 {status}\r\nContent-Length: 0\r\n\r\n");
+        writer.write_all(response.as_bytes())?;
+    }
+}
+
+// "GET /test?file=foo&signature=abcd HTTP/1.1" -> ("foo", [0xab, 0xcd])
+fn parse_query(request_line: &str) -> Option<(String, Vec<u8>)> {
+    let target = request_line.strip_prefix("GET ")?.split(' ').next()?;
+    let query = target.strip_prefix("/test?")?;
+
+    let mut file = None;
+    let mut signature = None;
+    for pair in query.split('&') {
+        match pair.split_once('=')? {
+            ("file", value) => file = Some(value.to_string()),
+            ("signature", value) => signature = decode_hex(value.as_bytes()).ok(),
+            _ => {}
+        }
+    }
+
+    Some((file?, signature?))
+}
+
+/// A keep-alive connection to an `HmacServer`.
+pub struct HmacClient {
+    stream: BufReader<TcpStream>,
+}
+
+impl HmacClient {
+    pub fn connect(addr: SocketAddr) -> io::Result<Self> {
+        let stream = TcpStream::connect(addr)?;
+        stream.set_nodelay(true)?;
+
+        Ok(Self { stream: BufReader::new(stream) })
+    }
+
+    /// Asks whether `signature` is valid for `file`, returning the HTTP
+    /// status code.
+    pub fn request(&mut self, file: &str, signature: &[u8]) -> io::Result<u16> {
+        let request = format!(
+            "GET /test?file={file}&signature={} HTTP/1.1\r\nHost: localhost\r\n\r\n",
+            to_hex(signature));
+        self.stream.get_mut().write_all(request.as_bytes())?;
+
+        let mut status_line = String::new();
+        self.stream.read_line(&mut status_line)?;
+
+        // No body, so the response ends at the first blank line
+        let mut header = String::new();
+        while self.stream.read_line(&mut header)? > 2 {
+            header.clear();
+        }
+
+        status_line
+            .split(' ')
+            .nth(1)
+            .and_then(|x| x.parse().ok())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "malformed response"))
+    }
+}
+
+/// One-off `HmacClient::request` on a fresh connection.
+pub fn request(addr: SocketAddr, file: &str, signature: &[u8]) -> io::Result<u16> {
+    HmacClient::connect(addr)?.request(file, signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::set_four::challenge_twenty_eight::Sha1;
+
+    fn check(key: &[u8], msg: &[u8], expected: &str) {
+        assert_eq!(to_hex(&hmac::<Sha1>(key, msg)), expected);
+    }
+
+    #[test]
+    fn rfc_vectors() {
+        // RFC 2202
+        check(&[0x0B; 20], b"Hi There", "b617318655057264e28bc0b6fb378c8ef146be00");
+        check(b"Jefe", b"what do ya want for nothing?", "effcdf6ae5eb2fa2d27416d5f184df9c259a7c79");
+        check(
+            &[0xAA; 80],
+            b"Test Using Larger Than Block-Size Key - Hash Key First",
+            "aa4ae5e15272d00e95705637ce8a3b55ed402112");
+    }
+
+    #[test]
+    fn server() {
+        let key = b"YELLOW SUBMARINE".to_vec();
+        let signature = hmac::<Sha1>(&key, b"foo");
+        let server = HmacServer::spawn::<Sha1>(key, Duration::from_micros(1))
+            .expect("couldn't start server");
+
+        assert_eq!(request(server.addr(), "foo", &signature).unwrap(), 200);
+        assert_eq!(request(server.addr(), "bar", &signature).unwrap(), 500);
+        assert_eq!(request(server.addr(), "foo", &signature[..19]).unwrap(), 500);
+    }
+}
@@ -0,0 +1,195 @@
+use std::{io, net::SocketAddr, thread, time::{Duration, Instant}};
+
+use super::challenge_thirty_one::HmacClient;
+
+/// How many of the slowest bytes from a quick pass get measured properly
+const SHORTLIST_LEN: usize = 16;
+
+/// Requests per byte in the quick pass
+const QUICK_SAMPLES: usize = 3;
+
+/// How the attack spends its requests.
+pub struct TimingAttack {
+    addr: SocketAddr,
+    file: String,
+    signature_len: usize,
+    samples: usize,
+    concurrency: usize,
+}
+
+impl TimingAttack {
+    /// `samples` is requests per shortlisted byte. Only the fastest one
+    /// counts: the server can be made slower by noise, never faster.
+    /// `concurrency` is requests in flight at once. The server's delay is a
+    /// sleep, so this divides the running time without blurring the timings
+    /// much. None of the three can be zero.
+    pub fn new(addr: SocketAddr, file: &str, signature_len: usize, samples: usize, concurrency: usize) -> Self {
+        assert!(signature_len > 0, "signature can't be empty");
+        assert!(samples > 0, "need at least one sample per byte");
+        assert!(concurrency > 0, "need at least one connection");
+
+        Self { addr, file: file.to_string(), signature_len, samples, concurrency }
+    }
+
+    /// Recovers a valid signature for `file` one byte at a time from how
+    /// long the server takes to reject guesses. Returns None if it gives up.
+    pub fn run(&self) -> io::Result<Option<Vec<u8>>> {
+        let mut known: Vec<u8> = Vec::new();
+        // How much slower each settled byte was than its competition
+        let mut margins: Vec<Duration> = Vec::new();
+        // Passes in a row where nothing stood out at this position
+        let mut misses = 0;
+
+        // Every byte costs at least one pass; give up after a few retries
+        for _ in 0..self.signature_len * 4 {
+            if known.len() == self.signature_len - 1 {
+                // No need for timing here: the server just tells us
+                if let Some(last) = self.find_last_byte(&known)? {
+                    known.push(last);
+                    return Ok(Some(known));
+                }
+
+                known.pop();
+                margins.pop();
+                continue;
+            }
+
+            let (byte, margin) = self.guess_next_byte(&known)?;
+
+            // With a wrong byte already in there, every guess fails at the
+            // same point and nothing stands out. Compare against how much
+            // the right bytes stood out so far. A noisy pass can miss the
+            // right byte too, so look again before blaming the last one.
+            if !margins.is_empty() {
+                let typical = margins.iter().sum::<Duration>() / margins.len() as u32;
+                if margin < typical / 2 {
+                    misses += 1;
+                    if misses == 2 {
+                        known.pop();
+                        margins.pop();
+                        misses = 0;
+                    }
+                    continue;
+                }
+            }
+
+            known.push(byte);
+            margins.push(margin);
+            misses = 0;
+        }
+
+        Ok(None)
+    }
+
+    // Returns the likeliest next byte and how much slower it was than the
+    // median of the shortlist.
+    fn guess_next_byte(&self, known: &[u8]) -> io::Result<(u8, Duration)> {
+        // A quick look at everything. One sample each lets a stray stall
+        // push the right byte out of the shortlist, so take a few.
+        let all = (0..=255).collect::<Vec<u8>>();
+        let mut quick = self.time_all(known, &all, QUICK_SAMPLES)?;
+        quick.sort_by_key(|(_, time)| std::cmp::Reverse(*time));
+
+        // Then a careful look at the slowest few
+        let shortlist = quick[..SHORTLIST_LEN].iter().map(|(byte, _)| *byte).collect::<Vec<u8>>();
+        let mut careful = self.time_all(known, &shortlist, self.samples)?;
+        careful.sort_by_key(|(_, time)| std::cmp::Reverse(*time));
+
+        let (byte, time) = careful[0];
+        let median = careful[SHORTLIST_LEN / 2].1;
+
+        Ok((byte, time.saturating_sub(median)))
+    }
+
+    // The fastest of `samples` response times for each of `bytes` as the
+    // next byte, spread over `concurrency` connections.
+    fn time_all(&self, known: &[u8], bytes: &[u8], samples: usize) -> io::Result<Vec<(u8, Duration)>> {
+        let per_thread = bytes.len().div_ceil(self.concurrency);
+
+        thread::scope(|s| {
+            let workers = bytes
+                .chunks(per_thread)
+                .map(|chunk| s.spawn(move || {
+                    let mut client = HmacClient::connect(self.addr)?;
+
+                    chunk
+                        .iter()
+                        .map(|byte| {
+                            let signature = self.guess(known, *byte);
+                            let fastest = (0..samples)
+                                .map(|_| self.time(&mut client, &signature))
+                                .collect::<io::Result<Vec<Duration>>>()?
+                                .into_iter()
+                                .min()
+                                .unwrap_or_default();
+
+                            Ok((*byte, fastest))
+                        })
+                        .collect::<io::Result<Vec<(u8, Duration)>>>()
+                }))
+                .collect::<Vec<_>>();
+
+            workers
+                .into_iter()
+                .map(|worker| worker.join().expect("timing thread panicked"))
+                .collect::<io::Result<Vec<Vec<(u8, Duration)>>>>()
+                .map(|x| x.concat())
+        })
+    }
+
+    fn find_last_byte(&self, known: &[u8]) -> io::Result<Option<u8>> {
+        let mut client = HmacClient::connect(self.addr)?;
+        for byte in 0..=255 {
+            if client.request(&self.file, &self.guess(known, byte))? == 200 {
+                return Ok(Some(byte));
+            }
+        }
+
+        Ok(None)
+    }
+
+    // known || byte, zero padded to the signature length
+    fn guess(&self, known: &[u8], byte: u8) -> Vec<u8> {
+        let mut signature = known.to_vec();
+        signature.push(byte);
+        signature.resize(self.signature_len, 0);
+
+        signature
+    }
+
+    fn time(&self, client: &mut HmacClient, signature: &[u8]) -> io::Result<Duration> {
+        let start = Instant::now();
+        client.request(&self.file, signature)?;
+
+        Ok(start.elapsed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::set_four::{
+        challenge_twenty_eight::Sha1,
+        challenge_thirty_one::{hmac, request, HmacServer},
+    };
+    use crate::set_two::challenge_eleven::random_aes_key;
+
+    #[test]
+    fn example() {
+        let key = random_aes_key();
+        let expected = hmac::<Sha1>(&key, b"foo");
+
+        // Small enough to finish quickly, big enough to stand out from the
+        // noise of a loopback connection.
+        let server = HmacServer::spawn::<Sha1>(key, Duration::from_millis(1))
+            .expect("couldn't start server");
+
+        let attack = TimingAttack::new(server.addr(), "foo", 20, 5, 16);
+        let signature = attack.run()
+            .expect("couldn't talk to server")
+            .expect("couldn't recover signature");
+
+        assert_eq!(signature, expected);
+        assert_eq!(request(server.addr(), "foo", &signature).unwrap(), 200);
+    }
+}
@@ -0,0 +1,174 @@
+//! SHA-1, per FIPS 180-4.
+use super::challenge_thirty::{prefix_mac, Buffered, MerkleDamgard};
+
+const INITIAL_STATE: [u32; 5] = [0x6745_2301, 0xEFCD_AB89, 0x98BA_DCFE, 0x1032_5476, 0xC3D2_E1F0];
+
+#[derive(Clone)]
+pub struct Sha1(pub Buffered<[u32; 5]>);
+
+impl MerkleDamgard for Sha1 {
+    type State = [u32; 5];
+    type Digest = [u8; 20];
+
+    const INITIAL_STATE: [u32; 5] = INITIAL_STATE;
+
+    fn from_buffered(buffered: Buffered<[u32; 5]>) -> Self {
+        Self(buffered)
+    }
+
+    fn buffered(&mut self) -> &mut Buffered<[u32; 5]> {
+        &mut self.0
+    }
+
+    fn compress(state: &mut [u32; 5], block: &[u8]) {
+        compress(state, block)
+    }
+
+    fn padding(len: u64) -> Vec<u8> {
+        padding(len)
+    }
+
+    fn digest_from_state(state: &[u32; 5]) -> [u8; 20] {
+        let mut digest = [0u8; 20];
+        for (bytes, word) in digest.chunks_mut(4).zip(state) {
+            bytes.copy_from_slice(&word.to_be_bytes());
+        }
+
+        digest
+    }
+
+    fn state_from_digest(digest: &[u8; 20]) -> [u32; 5] {
+        let mut state = [0u32; 5];
+        for (word, bytes) in state.iter_mut().zip(digest.chunks(4)) {
+            *word = u32::from_be_bytes(bytes.try_into().unwrap());
+        }
+
+        state
+    }
+}
+
+impl Default for Sha1 {
+    fn default() -> Self {
+        <Self as MerkleDamgard>::new()
+    }
+}
+
+/// The padding SHA-1 appends to a message of `len` bytes: a 1 bit, zeroes
+/// up to 56 bytes mod 64, then the message length in bits, big endian.
+pub fn padding(len: u64) -> Vec<u8> {
+    let mut padding = vec![0x80];
+    padding.extend(std::iter::repeat_n(0, (119 - len as usize % 64) % 64));
+    padding.extend((len * 8).to_be_bytes());
+
+    padding
+}
+
+fn compress(state: &mut [u32; 5], block: &[u8]) {
+    let mut w = [0u32; 80];
+    for (word, bytes) in w.iter_mut().zip(block.chunks(4)) {
+        *word = u32::from_be_bytes(bytes.try_into().unwrap());
+    }
+    for i in 16..80 {
+        w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e] = *state;
+    for (i, word) in w.iter().enumerate() {
+        let (f, k) = match i {
+            0..=19 => ((b & c) | (!b & d), 0x5A82_7999),
+            20..=39 => (b ^ c ^ d, 0x6ED9_EBA1),
+            40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1B_BCDC),
+            _ => (b ^ c ^ d, 0xCA62_C1D6),
+        };
+
+        let temp = a
+            .rotate_left(5)
+            .wrapping_add(f)
+            .wrapping_add(e)
+            .wrapping_add(k)
+            .wrapping_add(*word);
+        e = d;
+        d = c;
+        c = b.rotate_left(30);
+        b = a;
+        a = temp;
+    }
+
+    for (word, x) in state.iter_mut().zip([a, b, c, d, e]) {
+        *word = word.wrapping_add(x);
+    }
+}
+
+/// MAC(key, message) = SHA1(key || message). Don't do this.
+pub fn secret_prefix_mac(key: &[u8], msg: &[u8]) -> [u8; 20] {
+    prefix_mac::<Sha1>(key, msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::set_one::challenge_one::decode_hex;
+
+    fn check(input: &[u8], expected: &str) {
+        let expected = decode_hex(expected.as_bytes()).unwrap();
+        assert_eq!(Sha1::digest(input).to_vec(), expected, "{:?}", String::from_utf8_lossy(input));
+    }
+
+    #[test]
+    fn fips_vectors() {
+        check(b"abc", "a9993e364706816aba3e25717850c26c9cd0d89d");
+        check(b"", "da39a3ee5e6b4b0d3255bfef95601890afd80709");
+        check(
+            b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+            "84983e441c3bd26ebaae4aa1f95129e5e54670f1");
+        check(
+            b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu",
+            "a49b2446a02c645bf419f995b67091253a04a259");
+    }
+
+    #[test]
+    fn million_a() {
+        // Fed in awkwardly sized pieces to exercise the buffering
+        let mut sha1 = Sha1::new();
+        let chunk = [b'a'; 999];
+        for _ in 0..1001 {
+            sha1.update(&chunk);
+        }
+        sha1.update(&chunk[..1]);
+
+        let expected = decode_hex(b"34aa973cd4c4daa4f61eeb2bdbad27316534016f").unwrap();
+        assert_eq!(sha1.finalize().to_vec(), expected);
+    }
+
+    #[test]
+    fn resume_from_state() {
+        let msg = b"YELLOW SUBMARINE YELLOW SUBMARINE YELLOW SUBMARINE YELLOW SUBMARINE and then some";
+
+        // Hash the first block, then pick up where it left off
+        let mut first = Sha1::new();
+        first.update(&msg[..64]);
+        let mut resumed = Sha1::from_state(first.0.state, 64);
+        resumed.update(&msg[64..]);
+
+        assert_eq!(resumed.finalize(), Sha1::digest(msg));
+
+        let digest = Sha1::digest(b"abc");
+        let mut padded = b"abc".to_vec();
+        padded.extend(padding(3));
+        assert_eq!(Sha1::state_from_digest(&digest), {
+            let mut sha1 = Sha1::new();
+            sha1.update(&padded);
+            sha1.0.state
+        });
+    }
+
+    #[test]
+    fn mac() {
+        let key = b"YELLOW SUBMARINE";
+        let mac = secret_prefix_mac(key, b"hello");
+
+        assert_eq!(mac, Sha1::digest(b"YELLOW SUBMARINEhello"));
+        assert_ne!(mac, secret_prefix_mac(key, b"hellp"));
+        assert_ne!(mac, secret_prefix_mac(b"YELLOW SUBMARINF", b"hello"));
+    }
+}
@@ -0,0 +1,96 @@
+use eyre::{ensure, Result};
+
+use crate::set_three::challenge_eighteen::{aes_ctr, ctr_keystream_at};
+use crate::set_two::challenge_eleven::random_aes_key;
+
+/// Seekable CTR always runs under nonce 0.
+pub const NONCE: u64 = 0;
+
+/// Overwrites the plaintext of a CTR ciphertext (under key, nonce 0) from
+/// `offset` onwards with `newtext`, returning the new ciphertext. Only the
+/// edited bytes are re-encrypted; writing past the end grows the buffer.
+pub fn edit(ciphertext: &[u8], key: &[u8], offset: usize, newtext: &[u8]) -> Result<Vec<u8>> {
+    ensure!(offset <= ciphertext.len(), "offset past end of ciphertext");
+
+    let mut out = ciphertext[..offset].to_vec();
+    out.extend(
+        newtext
+            .iter()
+            .zip(ctr_keystream_at(key, NONCE, offset))
+            .map(|(x, y)| x ^ y));
+    out.extend(ciphertext.iter().skip(offset + newtext.len()));
+
+    Ok(out)
+}
+
+/// Holds a key and exposes `edit` without it.
+pub struct EditOracle {
+    key: Vec<u8>,
+}
+
+impl EditOracle {
+    pub fn new() -> Self {
+        Self { key: random_aes_key() }
+    }
+
+    pub fn encrypt(&self, plaintext: &[u8]) -> Vec<u8> {
+        aes_ctr(plaintext, &self.key, NONCE)
+    }
+
+    pub fn edit(&self, ciphertext: &[u8], offset: usize, newtext: &[u8]) -> Result<Vec<u8>> {
+        edit(ciphertext, &self.key, offset, newtext)
+    }
+}
+
+impl Default for EditOracle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Recovers the plaintext behind `ciphertext` through an edit oracle.
+/// "Editing" the ciphertext over itself XORs the keystream out of it.
+pub fn recover_plaintext<F>(ciphertext: &[u8], edit: F) -> Result<Vec<u8>>
+where
+    F: Fn(&[u8], usize, &[u8]) -> Result<Vec<u8>>,
+{
+    edit(ciphertext, 0, ciphertext)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::set_one::{challenge_seven::aes_ecb_dec, challenge_six::base64_decode};
+
+    #[test]
+    fn test_edit() {
+        let key = b"YELLOW SUBMARINE";
+        let ciphertext = aes_ctr(b"Hello, world! Hello, world! Hello, world!", key, NONCE);
+
+        let edited = edit(&ciphertext, key, 21, b"WORLD").expect("couldn't edit");
+        assert_eq!(edited.len(), ciphertext.len());
+        assert_eq!(edited[..21], ciphertext[..21]);
+        assert_eq!(edited[26..], ciphertext[26..]);
+        assert_eq!(&aes_ctr(&edited, key, NONCE), b"Hello, world! Hello, WORLD! Hello, world!");
+
+        let grown = edit(&ciphertext, key, 39, b"!!!!").expect("couldn't edit");
+        assert_eq!(&aes_ctr(&grown, key, NONCE), b"Hello, world! Hello, world! Hello, worl!!!!");
+
+        assert!(edit(&ciphertext, key, 100, b"!").is_err());
+    }
+
+    #[test]
+    fn example() {
+        let inp = include_str!("../../vendor/challenge-seven.txt").replace('\n', "");
+        let plaintext = aes_ecb_dec(&base64_decode(&inp), b"YELLOW SUBMARINE")
+            .expect("couldn't decrypt example aes message");
+
+        let oracle = EditOracle::new();
+        let ciphertext = oracle.encrypt(&plaintext);
+
+        let res = recover_plaintext(&ciphertext, |ct, offset, newtext| oracle.edit(ct, offset, newtext))
+            .expect("edit oracle failed");
+
+        assert_eq!(res, plaintext);
+    }
+}
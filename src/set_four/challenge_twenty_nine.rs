@@ -0,0 +1,96 @@
+use rand::seq::SliceRandom;
+
+use super::challenge_twenty_eight::{secret_prefix_mac, Sha1};
+use super::challenge_thirty::{forge_mac, length_extension_attack};
+
+const WORDS: &str = include_str!("../../vendor/words.txt");
+
+/// The longest key we bother guessing
+pub const MAX_KEY_LEN: usize = 64;
+
+/// Given MAC(key || msg), forges a MAC for
+/// msg || glue padding || extension, assuming the key is `key_len` bytes.
+/// Returns the forged message (sans key) and its MAC.
+pub fn forge_sha1_mac(
+    mac: &[u8; 20],
+    msg: &[u8],
+    key_len: usize,
+    extension: &[u8],
+) -> (Vec<u8>, [u8; 20])
+{
+    forge_mac::<Sha1>(mac, msg, key_len, extension)
+}
+
+/// Keeps a secret key (a random word) and checks MACs made with it.
+pub struct MacOracle {
+    key: Vec<u8>,
+}
+
+impl MacOracle {
+    pub fn new() -> Self {
+        let words = WORDS.lines().collect::<Vec<&str>>();
+        let key = words.choose(&mut rand::thread_rng()).unwrap();
+
+        Self { key: key.as_bytes().to_vec() }
+    }
+
+    pub fn sign(&self, msg: &[u8]) -> [u8; 20] {
+        secret_prefix_mac(&self.key, msg)
+    }
+
+    pub fn verify(&self, msg: &[u8], mac: &[u8; 20]) -> bool {
+        &self.sign(msg) == mac
+    }
+}
+
+impl Default for MacOracle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Forges a SHA-1 MAC for msg || glue padding || extension by trying every
+/// key length up to `MAX_KEY_LEN` until `verify` accepts one.
+pub fn sha1_length_extension_attack<F>(
+    mac: &[u8; 20],
+    msg: &[u8],
+    extension: &[u8],
+    verify: F,
+) -> Option<(Vec<u8>, [u8; 20])>
+where
+    F: Fn(&[u8], &[u8; 20]) -> bool,
+{
+    length_extension_attack::<Sha1, _>(mac, msg, extension, MAX_KEY_LEN, verify)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MSG: &[u8] = b"comment1=cooking%20MCs;userdata=foo;comment2=%20like%20a%20pound%20of%20bacon";
+
+    #[test]
+    fn forge_known_key_len() {
+        let key = b"YELLOW SUBMARINE";
+        let mac = secret_prefix_mac(key, MSG);
+
+        let (forged, forged_mac) = forge_sha1_mac(&mac, MSG, key.len(), b";admin=true");
+
+        assert_eq!(secret_prefix_mac(key, &forged), forged_mac);
+        assert!(forged.starts_with(MSG));
+        assert!(forged.ends_with(b";admin=true"));
+    }
+
+    #[test]
+    fn example() {
+        let oracle = MacOracle::new();
+        let mac = oracle.sign(MSG);
+
+        let (forged, forged_mac) = sha1_length_extension_attack(&mac, MSG, b";admin=true", |msg, mac| {
+            oracle.verify(msg, mac)
+        }).expect("no key length worked");
+
+        assert!(oracle.verify(&forged, &forged_mac));
+        assert!(forged.ends_with(b";admin=true"));
+    }
+}
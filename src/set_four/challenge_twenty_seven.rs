@@ -0,0 +1,114 @@
+use std::fmt;
+use eyre::Result;
+
+use crate::set_two::{
+    challenge_eleven::{aes_cbc_enc_with_iv, random_aes_key},
+    challenge_fifteen::strip_pkcs7,
+    challenge_ten::aes_cbc_dec,
+};
+
+/// What the service complains with when a plaintext isn't plain ASCII.
+/// Helpfully, it includes the offending plaintext.
+#[derive(Debug)]
+pub struct InvalidAscii {
+    pub plaintext: Vec<u8>,
+}
+
+impl fmt::Display for InvalidAscii {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid ascii in plaintext: {:?}", String::from_utf8_lossy(&self.plaintext))
+    }
+}
+
+impl std::error::Error for InvalidAscii {}
+
+/// A CBC service that saves itself generating IVs by reusing the key.
+pub struct KeyAsIvService {
+    key: Vec<u8>,
+}
+
+impl KeyAsIvService {
+    pub fn new() -> Self {
+        Self { key: random_aes_key() }
+    }
+
+    pub fn encrypt(&self, plaintext: &[u8]) -> Vec<u8> {
+        aes_cbc_enc_with_iv(plaintext, &self.key, &self.key)
+    }
+
+    /// Decrypts `ciphertext`, failing with `InvalidAscii` if any byte of the
+    /// plaintext is high-ASCII.
+    pub fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        let plaintext = aes_cbc_dec(ciphertext, &self.key, &self.key)?;
+
+        if !plaintext.is_ascii() {
+            return Err(InvalidAscii { plaintext }.into());
+        }
+
+        strip_pkcs7(&plaintext)
+    }
+}
+
+impl Default for KeyAsIvService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Recovers the key (and IV) from a ciphertext of at least three blocks.
+///
+/// Sending C1, 0, C1 gives P1 = D(C1) ^ IV and P3 = D(C1) ^ 0, so
+/// P1 ^ P3 = IV.
+pub fn recover_key<F>(ciphertext: &[u8], decrypt: F) -> Option<Vec<u8>>
+where
+    F: Fn(&[u8]) -> Result<Vec<u8>>,
+{
+    if ciphertext.len() < 48 {
+        return None;
+    }
+
+    let first = &ciphertext[..16];
+    let mut forged = first.to_vec();
+    forged.extend([0u8; 16]);
+    forged.extend(first);
+
+    let err = decrypt(&forged).err()?;
+    let plaintext = &err.downcast_ref::<InvalidAscii>()?.plaintext;
+
+    Some(
+        plaintext[..16]
+            .iter()
+            .zip(&plaintext[32..48])
+            .map(|(x, y)| x ^ y)
+            .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let service = KeyAsIvService::new();
+        let plaintext = b"Test string! Test string! Test string! Test string!";
+
+        let res = service.decrypt(&service.encrypt(plaintext)).expect("couldn't decrypt");
+        assert_eq!(&res, plaintext);
+
+        // Not whole blocks is an error, and not one that leaks a plaintext
+        let err = service.decrypt(&[0u8; 40]).unwrap_err();
+        assert!(err.downcast_ref::<InvalidAscii>().is_none());
+    }
+
+    #[test]
+    fn example() {
+        let service = KeyAsIvService::new();
+        let ciphertext = service.encrypt(b"comment1=cooking%20MCs;userdata=hello;comment2=%20like");
+
+        let key = recover_key(&ciphertext, |ct| service.decrypt(ct))
+            .expect("couldn't recover key");
+
+        assert_eq!(key, service.key);
+        assert!(recover_key(&ciphertext[..32], |ct| service.decrypt(ct)).is_none());
+    }
+}
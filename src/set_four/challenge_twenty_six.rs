@@ -0,0 +1,44 @@
+use crate::set_two::challenge_sixteen::{CommentService, PREFIX};
+
+/// Gets a CTR comment service to grant admin. Flipping a ciphertext bit
+/// flips the same plaintext bit and nothing else, so no filler is needed.
+pub fn ctr_bitflip(service: &CommentService) -> Vec<u8> {
+    let mut ciphertext = service.encrypt(b":admin<true");
+
+    ciphertext[PREFIX.len()] ^= 1;
+    ciphertext[PREFIX.len() + 6] ^= 1;
+
+    ciphertext
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::set_two::challenge_sixteen::{cbc_bitflip, CipherMode};
+
+    fn attack(mode: CipherMode, bitflip: fn(&CommentService) -> Vec<u8>) -> (CommentService, Vec<u8>) {
+        let service = CommentService::new(mode);
+        assert_eq!(service.mode(), mode);
+
+        let ciphertext = bitflip(&service);
+        assert!(service.is_admin(&ciphertext), "{mode:?} service didn't grant admin");
+
+        (service, ciphertext)
+    }
+
+    #[test]
+    fn example() {
+        attack(CipherMode::Cbc, cbc_bitflip);
+        attack(CipherMode::Ctr, ctr_bitflip);
+    }
+
+    #[test]
+    fn no_collateral_damage() {
+        let (service, ciphertext) = attack(CipherMode::Ctr, ctr_bitflip);
+
+        let mut expected = PREFIX.to_vec();
+        expected.extend(b";admin=true;comment2=%20like%20a%20pound%20of%20bacon");
+
+        assert_eq!(service.decrypt(&ciphertext).unwrap(), expected);
+    }
+}
@@ -44,7 +44,7 @@ fn determine_keysize(input: &[u8]) -> Option<usize> {
 }
 
 // [[a, b, c], [d, e, f], [g, h, i]] -> [[a, d, g], [b, e, h], [c, f, i]]
-fn transpose_ciphertext(ciphertext: &[u8], keysize: usize) -> Vec<Vec<u8>> {
+pub fn transpose_ciphertext(ciphertext: &[u8], keysize: usize) -> Vec<Vec<u8>> {
     let blocks = ciphertext.chunks(keysize);
 
     let mut transposed_blocks = Vec::new();
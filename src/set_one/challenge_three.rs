@@ -12,7 +12,7 @@ const LETTER_FREQUENCIES: [(char, f64); 26] = [
 // returns the top 5 closest matches by chi2 test
 pub fn brute_force_single_byte_xor(input: &[u8]) -> Result<Vec<(String, u8, f64)>> {
     let mut out = Vec::new();
-    for i in 0..255 {
+    for i in 0..=255 {
         let decoded = input
             .iter()
             .map(|x| (x ^ i) as char)
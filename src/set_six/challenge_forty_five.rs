@@ -0,0 +1,74 @@
+use crate::bigint::BigUint;
+use crate::dsa::{Params, PublicKey, Signature};
+use crate::rsa::invmod;
+
+/// The same group with g swapped out, as a malicious parameter supplier
+/// would hand it over.
+pub fn with_generator(params: &Params, g: BigUint) -> Params {
+    Params::new(params.p.clone(), params.q.clone(), g)
+}
+
+/// With g = 0, g^u1 * y^u2 is 0 whatever the message, so (0, s) verifies
+/// for anything if the verifier doesn't insist on 0 < r.
+pub fn zero_generator_signature() -> Signature {
+    Signature { r: BigUint::zero(), s: BigUint::one() }
+}
+
+/// With g = p + 1 the verifier computes v = y^u2 = y^(r / s). Picking
+/// r = y^z mod p mod q and s = r / z makes that y^z again, for any message
+/// and any z invertible mod q.
+pub fn magic_signature(key: &PublicKey, z: &BigUint) -> Option<Signature> {
+    let Params { p, q, .. } = &key.params;
+
+    let r = key.y.modpow(z, p) % q;
+    let s = &r * invmod(z, q)? % q;
+
+    Some(Signature { r, s })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_generator() {
+        let params = with_generator(&Params::cryptopals(), BigUint::zero());
+        let key = params.generate_keypair();
+
+        // Honest signatures come out with r = 0 too, and a careful verifier
+        // turns them down along with the forgery
+        let honest = key.sign(b"Hello, world");
+        assert!(honest.r.is_zero());
+        assert!(!key.public.verify(b"Hello, world", &honest));
+
+        let forged = zero_generator_signature();
+        for msg in [&b"Hello, world"[..], b"Goodbye, world"] {
+            assert!(key.public.verify_unchecked(msg, &forged));
+            assert!(!key.public.verify(msg, &forged));
+        }
+    }
+
+    #[test]
+    fn example() {
+        let params = Params::cryptopals();
+        let key = params.generate_keypair();
+
+        // The victim's real key, checked against a tampered g
+        let g = &params.p + 1;
+        let tampered = PublicKey { params: with_generator(&params, g), y: key.public.y.clone() };
+
+        let mut rng = rand::thread_rng();
+        for msg in [&b"Hello, world"[..], b"Goodbye, world"] {
+            let z = BigUint::random_below(&(&params.q - 1), &mut rng) + 1;
+            let forged = magic_signature(&tampered, &z).unwrap();
+
+            assert!(tampered.verify(msg, &forged));
+            assert!(!key.public.verify(msg, &forged));
+        }
+
+        // Whereas a genuine signature doesn't survive the swap
+        let honest = key.sign(b"Hello, world");
+        assert!(key.public.verify(b"Hello, world", &honest));
+        assert!(!tampered.verify(b"Hello, world", &honest));
+    }
+}
@@ -0,0 +1,126 @@
+use eyre::{ensure, eyre, Result};
+
+use crate::bigint::BigUint;
+use crate::dsa::{hash_message, Params, PublicKey, Signature};
+use crate::rsa::invmod;
+use super::challenge_forty_three::private_from_nonce;
+
+/// One entry from a list of signed messages: the message, its signature and
+/// the H(m) it was signed over.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SignedMessage {
+    pub msg: String,
+    pub signature: Signature,
+    pub hash: BigUint,
+}
+
+fn field<'a>(line: &'a str, name: &str) -> Result<&'a str> {
+    line.strip_prefix(name).ok_or_else(|| eyre!("expected {name:?}, got {line:?}"))
+}
+
+/// Reads the msg/s/r/m blocks of `vendor/challenge-forty-four.txt`, checking
+/// each m against the message.
+pub fn parse_signed_messages(input: &str) -> Result<Vec<SignedMessage>> {
+    ensure!(input.lines().count().is_multiple_of(4), "expected blocks of four lines");
+
+    input
+        .lines()
+        .array_chunks::<4>()
+        .map(|[msg, s, r, m]| {
+            let msg = field(msg, "msg: ")?.to_string();
+            let signature = Signature { r: field(r, "r: ")?.parse()?, s: field(s, "s: ")?.parse()? };
+            let hash = BigUint::from_hex(field(m, "m: ")?.as_bytes())?;
+            ensure!(hash == hash_message(msg.as_bytes()), "m doesn't match {msg:?}");
+
+            Ok(SignedMessage { msg, signature, hash })
+        })
+        .collect()
+}
+
+/// Pairs of different messages that were signed with the same k, which
+/// shows up as the same r.
+pub fn find_repeated_nonces(messages: &[SignedMessage]) -> Vec<(usize, usize)> {
+    let mut pairs = Vec::new();
+    for (i, a) in messages.iter().enumerate() {
+        for (j, b) in messages.iter().enumerate().skip(i + 1) {
+            if a.signature.r == b.signature.r && a.signature.s != b.signature.s {
+                pairs.push((i, j));
+            }
+        }
+    }
+
+    pairs
+}
+
+/// The shared k behind two signatures: k = (m1 - m2) / (s1 - s2) mod q.
+pub fn nonce_from_pair(q: &BigUint, a: &SignedMessage, b: &SignedMessage) -> Option<BigUint> {
+    let sub = |x: &BigUint, y: &BigUint| (x % q + q - y % q) % q;
+
+    let ds_inv = invmod(&sub(&a.signature.s, &b.signature.s), q)?;
+    Some(sub(&a.hash, &b.hash) * ds_inv % q)
+}
+
+/// Finds a pair of messages with a repeated nonce and recovers the private
+/// key from it, checked against y.
+pub fn recover_from_repeated_nonce(key: &PublicKey, messages: &[SignedMessage]) -> Option<BigUint> {
+    let Params { p, q, g } = &key.params;
+
+    find_repeated_nonces(messages).into_iter().find_map(|(i, j)| {
+        let k = nonce_from_pair(q, &messages[i], &messages[j])?;
+        let x = private_from_nonce(q, &messages[i].hash, &messages[i].signature, &k)?;
+
+        (g.modpow(&x, p) == key.y).then_some(x)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::set_four::{challenge_thirty::MerkleDamgard, challenge_twenty_eight::Sha1};
+    use crate::set_one::challenge_one::decode_hex;
+
+    const SIGNED: &str = include_str!("../../vendor/challenge-forty-four.txt");
+
+    const Y: &str = "
+        2d026f4bf30195ede3a088da85e398ef869611d0f68f0713d51c9c1a3a26c95105d915e2d8cdf26d
+        056b86b8a7b85519b1c23cc3ecdc6062650462e3063bd179c2a6581519f674a61f1d89a1fff27171
+        ebc1b93d4dc57bceb7ae2430f98a6a4d83d8279ee65d71c1203d2c96d65ebbf7cce9d32971c3de50
+        84cce04a2e147821";
+
+    #[test]
+    fn example() {
+        let key = PublicKey { params: Params::cryptopals(), y: BigUint::from_hex(Y.as_bytes()).unwrap() };
+        let messages = parse_signed_messages(SIGNED).unwrap();
+        assert_eq!(messages.len(), SIGNED.lines().filter(|line| line.starts_with("msg: ")).count());
+        assert!(messages.iter().all(|m| key.verify(m.msg.as_bytes(), &m.signature)));
+
+        // These two went out with the same k, and the rest didn't
+        let pairs = find_repeated_nonces(&messages)
+            .into_iter()
+            .map(|(i, j)| (messages[i].msg.as_str(), messages[j].msg.as_str()))
+            .collect::<Vec<_>>();
+        assert!(pairs.contains(&(
+            "Listen for me, you better listen for me now. ",
+            "Pure black people mon is all I mon know. ")));
+        assert!(pairs.len() < messages.len() * (messages.len() - 1) / 2);
+
+        let x = recover_from_repeated_nonce(&key, &messages).expect("no repeated nonce gave the key");
+        assert_eq!(
+            Sha1::digest(x.to_hex().as_bytes()).to_vec(),
+            decode_hex(b"ca8f6f7c66fa362d40760d135b763eb8527d3d52").unwrap());
+    }
+
+    #[test]
+    fn fresh_nonces() {
+        let key = Params::cryptopals().generate_keypair();
+        let messages = ["one", "two", "three"]
+            .map(|msg| SignedMessage {
+                msg: msg.to_string(),
+                signature: key.sign(msg.as_bytes()),
+                hash: hash_message(msg.as_bytes()),
+            });
+
+        assert!(find_repeated_nonces(&messages).is_empty());
+        assert_eq!(recover_from_repeated_nonce(&key.public, &messages), None);
+    }
+}
@@ -0,0 +1,83 @@
+use std::collections::HashSet;
+
+use eyre::{ensure, Result};
+
+use crate::bigint::BigUint;
+use crate::rsa::{invmod, Keypair, PublicKey};
+use crate::set_four::challenge_thirty::MerkleDamgard;
+use crate::set_five::challenge_thirty_six::Sha256;
+
+/// Decrypts anything, once. It remembers a hash of every ciphertext it's
+/// seen and won't decrypt the same one again.
+pub struct DecryptionOracle {
+    key: Keypair,
+    seen: HashSet<[u8; 32]>,
+}
+
+impl DecryptionOracle {
+    pub fn new(key: Keypair) -> Self {
+        Self { key, seen: HashSet::new() }
+    }
+
+    pub fn public_key(&self) -> &PublicKey {
+        &self.key.public
+    }
+
+    pub fn decrypt(&mut self, c: &BigUint) -> Result<BigUint> {
+        // Otherwise c + n would decrypt the same under a different hash
+        ensure!(c < &self.key.public.n, "ciphertext doesn't fit under the modulus");
+
+        let hash = Sha256::digest(&c.to_bytes_be_padded(self.key.public.size()));
+        ensure!(self.seen.insert(hash), "already decrypted that one");
+
+        Ok(self.key.private.decrypt(c))
+    }
+}
+
+/// Gets `c` decrypted by an oracle that's already decrypted it once. Sends
+/// s^e * c instead, which decrypts to s * m, then divides the s back out.
+pub fn unpadded_message_recovery<F>(key: &PublicKey, c: &BigUint, mut decrypt: F) -> Result<BigUint>
+where
+    F: FnMut(&BigUint) -> Result<BigUint>,
+{
+    let mut rng = rand::thread_rng();
+
+    // Almost anything works, as long as it can be divided out again
+    let (s, s_inv) = loop {
+        let s = BigUint::random_below(&(&key.n - 2), &mut rng) + 2;
+        if let Some(s_inv) = invmod(&s, &key.n) {
+            break (s, s_inv);
+        }
+    };
+
+    let blinded = key.encrypt(&s) * c % &key.n;
+    let blinded_plaintext = decrypt(&blinded)?;
+    ensure!(blinded_plaintext < key.n, "oracle answered with nonsense");
+
+    Ok(blinded_plaintext * s_inv % &key.n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prime::PrimeGenerator;
+
+    #[test]
+    fn example() {
+        let key = Keypair::generate_with(1024, 65537, &mut PrimeGenerator::seeded(41));
+        let mut oracle = DecryptionOracle::new(key);
+        let public = oracle.public_key().clone();
+
+        let msg = BigUint::from_bytes_be(br#"{time: 1356304276, social: '555-55-5555'}"#);
+        let c = public.encrypt(&msg);
+
+        // The victim's own request goes through, and blocks any repeat
+        assert_eq!(oracle.decrypt(&c).unwrap(), msg);
+        assert!(oracle.decrypt(&c).is_err());
+        // Nor does the same number in disguise
+        assert!(oracle.decrypt(&(&c + &public.n)).is_err());
+
+        let recovered = unpadded_message_recovery(&public, &c, |x| oracle.decrypt(x)).unwrap();
+        assert_eq!(recovered, msg);
+    }
+}
@@ -0,0 +1,109 @@
+use crate::bigint::BigUint;
+use crate::rsa::{Keypair, PublicKey};
+
+/// Decrypts anything, but only says whether the plaintext came out even.
+pub struct ParityOracle {
+    key: Keypair,
+}
+
+impl ParityOracle {
+    pub fn new(key: Keypair) -> Self {
+        Self { key }
+    }
+
+    pub fn public_key(&self) -> &PublicKey {
+        &self.key.public
+    }
+
+    pub fn is_even(&self, c: &BigUint) -> bool {
+        self.key.private.decrypt(c).is_even()
+    }
+}
+
+/// Recovers the plaintext behind `c` from a parity oracle. See
+/// `parity_attack_with_progress`.
+pub fn parity_attack<F>(key: &PublicKey, c: &BigUint, is_even: F) -> Vec<u8>
+where
+    F: Fn(&BigUint) -> bool,
+{
+    parity_attack_with_progress(key, c, is_even, |_| {})
+}
+
+/// Multiplying c by 2^e doubles the plaintext. With n odd, 2m mod n is even
+/// exactly when 2m didn't wrap, i.e. when m < n / 2. Each doubling halves
+/// the range m could be in, so after log2(n) of them there's one number
+/// left.
+///
+/// The bounds are kept as n * k / 2^i with exact integers, since floats
+/// lose the low bits long before the end. `progress` gets the upper bound
+/// after every step, which reads as the plaintext coming into focus.
+pub fn parity_attack_with_progress<F, P>(key: &PublicKey, c: &BigUint, is_even: F, mut progress: P) -> Vec<u8>
+where
+    F: Fn(&BigUint) -> bool,
+    P: FnMut(&[u8]),
+{
+    let double = key.encrypt(&BigUint::from(2u64));
+    let mut c = c.clone();
+
+    // m is in [n * k / 2^i, n * (k + 1) / 2^i)
+    let mut k = BigUint::zero();
+    let bits = key.n.bits();
+    for i in 1..=bits {
+        c = c * &double % &key.n;
+        k = k << 1;
+        if !is_even(&c) {
+            k += &BigUint::one();
+        }
+
+        let upper = (&key.n * (&k + 1)) >> i;
+        progress(&upper.to_bytes_be());
+    }
+
+    // Only one integer is left in the range: the lower bound rounded up
+    let m = (&key.n * &k + (BigUint::one() << bits) - 1) >> bits;
+    m.to_bytes_be()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prime::PrimeGenerator;
+    use crate::set_one::challenge_six::base64_decode;
+
+    #[test]
+    fn example() {
+        let key = Keypair::generate_with(1024, 65537, &mut PrimeGenerator::seeded(46));
+        let oracle = ParityOracle::new(key);
+        let public = oracle.public_key().clone();
+
+        let msg = base64_decode(
+            "VGhhdCdzIHdoeSBJIGZvdW5kIHlvdSBkb24ndCBwbGF5IGFyb3VuZCB3aXRoIHRoZSBGdW5reSBDb2xkIE1lZGluYQ==");
+        let c = public.encrypt(&BigUint::from_bytes_be(&msg));
+
+        let mut steps = Vec::new();
+        let recovered = parity_attack_with_progress(&public, &c, |x| oracle.is_even(x), |x| steps.push(x.to_vec()));
+        assert_eq!(recovered, msg);
+
+        // One step per bit, closing in from above
+        assert_eq!(steps.len(), 1024);
+        assert!(steps.windows(2).all(|w| BigUint::from_bytes_be(&w[1]) <= BigUint::from_bytes_be(&w[0])));
+        assert_eq!(steps.last().unwrap(), &msg);
+    }
+
+    #[test]
+    fn edges() {
+        let key = Keypair::generate_with(256, 65537, &mut PrimeGenerator::seeded(46));
+        let oracle = ParityOracle::new(key);
+        let public = oracle.public_key().clone();
+
+        let mut rng = rand::thread_rng();
+        let cases = [BigUint::zero(), BigUint::one(), &public.n - 1, &public.n / 2u64, &public.n / 2u64 + 1]
+            .into_iter()
+            .chain((0..10).map(|_| BigUint::random_below(&public.n, &mut rng)));
+
+        for m in cases {
+            let recovered = parity_attack(&public, &public.encrypt(&m), |x| oracle.is_even(x));
+            assert_eq!(BigUint::from_bytes_be(&recovered), m);
+        }
+    }
+}
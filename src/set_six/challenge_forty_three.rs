@@ -0,0 +1,79 @@
+use crate::bigint::BigUint;
+use crate::dsa::{hash_message, Params, PublicKey, Signature};
+use crate::rsa::invmod;
+
+/// The private key behind a signature made with a known nonce:
+/// x = (s * k - H(m)) / r mod q. None if r has no inverse.
+pub fn private_from_nonce(q: &BigUint, msg_hash: &BigUint, signature: &Signature, k: &BigUint) -> Option<BigUint> {
+    let r_inv = invmod(&signature.r, q)?;
+    let sk = &signature.s * k % q;
+    let diff = (sk + q - msg_hash % q) % q;
+
+    Some(diff * r_inv % q)
+}
+
+/// Finds the private key behind `signature` when the signer picked k below
+/// 2^`bits`. Walks g^k up one multiplication at a time until it gives the
+/// right r, then checks the key it implies against y.
+pub fn brute_force_nonce(key: &PublicKey, msg: &[u8], signature: &Signature, bits: u32) -> Option<BigUint> {
+    let Params { p, q, g } = &key.params;
+    let msg_hash = hash_message(msg);
+
+    let mut g_k = BigUint::one();
+    for k in 0..1u64 << bits {
+        if &g_k % q == signature.r {
+            let x = private_from_nonce(q, &msg_hash, signature, &BigUint::from(k));
+            if let Some(x) = x.filter(|x| g.modpow(x, p) == key.y) {
+                return Some(x);
+            }
+        }
+
+        g_k = g_k * g % p;
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dsa::Keypair;
+    use crate::set_four::{challenge_thirty::MerkleDamgard, challenge_twenty_eight::Sha1};
+    use crate::set_one::challenge_one::decode_hex;
+
+    const MSG: &[u8] = b"For those that envy a MC it can be hazardous to your health\n\
+        So be friendly, a matter of life and death, just like a etch-a-sketch\n";
+
+    const Y: &str = "
+        84ad4719d044495496a3201c8ff484feb45b962e7302e56a392aee4abab3e4bdebf2955b4736012f
+        21a08084056b19bcd7fee56048e004e44984e2f411788efdc837a0d2e5abb7b555039fd243ac01f0
+        fb2ed1dec568280ce678e931868d23eb095fde9d3779191b8c0299d6e07bbb283e6633451e535c45
+        513b2d33c99ea17";
+
+    #[test]
+    fn known_nonce() {
+        let params = Params::cryptopals();
+        let key = params.generate_keypair();
+        let k = BigUint::from(0xC0FFEE_u64);
+
+        let signature = key.sign_with_nonce(b"hi mom", &k);
+        let x = private_from_nonce(&params.q, &hash_message(b"hi mom"), &signature, &k).unwrap();
+        assert_eq!(Keypair::from_private(params, x).public, key.public);
+    }
+
+    #[test]
+    fn example() {
+        let key = PublicKey { params: Params::cryptopals(), y: BigUint::from_hex(Y.as_bytes()).unwrap() };
+        let signature = Signature {
+            r: "548099063082341131477253921760299949438196259240".parse().unwrap(),
+            s: "857042759984254168557880549501802188789837994940".parse().unwrap(),
+        };
+        assert_eq!(hash_message(MSG), BigUint::from_hex(b"d2d0714f014a9784047eaeccf956520045c45265").unwrap());
+        assert!(key.verify(MSG, &signature));
+
+        let x = brute_force_nonce(&key, MSG, &signature, 16).expect("no k below 2^16 works");
+        assert_eq!(
+            Sha1::digest(x.to_hex().as_bytes()).to_vec(),
+            decode_hex(b"0954edd5e0afe5542a4adf012611a91912a3ec16").unwrap());
+    }
+}
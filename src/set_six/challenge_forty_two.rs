@@ -0,0 +1,165 @@
+use eyre::{ensure, ContextCompat, Result};
+
+use crate::bigint::BigUint;
+use crate::rsa::{Keypair, PublicKey};
+use crate::set_four::{challenge_thirty::MerkleDamgard, challenge_twenty_eight::Sha1};
+use crate::set_five::challenge_thirty_six::Sha256;
+
+/// The hashes we know the ASN.1 DigestInfo header for.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HashAlgorithm {
+    Sha1,
+    Sha256,
+}
+
+impl HashAlgorithm {
+    /// DER for the DigestInfo sequence up to the digest itself, from RFC 8017.
+    pub fn digest_info_prefix(self) -> &'static [u8] {
+        match self {
+            HashAlgorithm::Sha1 => &[
+                0x30, 0x21, 0x30, 0x09, 0x06, 0x05, 0x2B, 0x0E, 0x03, 0x02, 0x1A, 0x05, 0x00, 0x04, 0x14,
+            ],
+            HashAlgorithm::Sha256 => &[
+                0x30, 0x31, 0x30, 0x0D, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01,
+                0x05, 0x00, 0x04, 0x20,
+            ],
+        }
+    }
+
+    pub fn digest(self, msg: &[u8]) -> Vec<u8> {
+        match self {
+            HashAlgorithm::Sha1 => Sha1::digest(msg).to_vec(),
+            HashAlgorithm::Sha256 => Sha256::digest(msg).to_vec(),
+        }
+    }
+
+    /// DigestInfo with `msg`'s digest on the end.
+    pub fn digest_info(self, msg: &[u8]) -> Vec<u8> {
+        [self.digest_info_prefix(), &self.digest(msg)].concat()
+    }
+}
+
+/// EMSA-PKCS1-v1_5: 00 01 FF .. FF 00 DigestInfo, `len` bytes long.
+pub fn encode(hash: HashAlgorithm, msg: &[u8], len: usize) -> Result<Vec<u8>> {
+    let digest_info = hash.digest_info(msg);
+    // At least eight bytes of FF
+    ensure!(len >= digest_info.len() + 11, "key too small for {hash:?}");
+
+    let mut encoded = vec![0x00, 0x01];
+    encoded.resize(len - digest_info.len() - 1, 0xFF);
+    encoded.push(0x00);
+    encoded.extend(digest_info);
+
+    Ok(encoded)
+}
+
+/// Signs `msg`, giving a signature as long as the modulus.
+pub fn sign(key: &Keypair, hash: HashAlgorithm, msg: &[u8]) -> Result<Vec<u8>> {
+    let encoded = encode(hash, msg, key.public.size())?;
+    let s = key.private.decrypt(&BigUint::from_bytes_be(&encoded));
+
+    Ok(s.to_bytes_be_padded(key.public.size()))
+}
+
+// s^e, as many bytes long as the modulus
+fn open_signature(key: &PublicKey, signature: &[u8]) -> Option<Vec<u8>> {
+    let s = BigUint::from_bytes_be(signature);
+    if signature.len() != key.size() || s >= key.n {
+        return None;
+    }
+
+    Some(key.encrypt(&s).to_bytes_be_padded(key.size()))
+}
+
+/// Checks a signature by re-encoding the message and comparing the whole
+/// block.
+pub fn verify(key: &PublicKey, hash: HashAlgorithm, msg: &[u8], signature: &[u8]) -> bool {
+    let Some(opened) = open_signature(key, signature) else { return false };
+
+    encode(hash, msg, key.size()).is_ok_and(|expected| opened == expected)
+}
+
+/// Checks a signature the way too many implementations did: walks the
+/// padding, finds the DigestInfo and the digest, and never notices whatever
+/// comes after them.
+pub fn verify_sloppy(key: &PublicKey, hash: HashAlgorithm, msg: &[u8], signature: &[u8]) -> bool {
+    let Some(opened) = open_signature(key, signature) else { return false };
+
+    let Some(rest) = opened.strip_prefix(&[0x00, 0x01]) else { return false };
+    let padding = rest.iter().take_while(|&&b| b == 0xFF).count();
+    let Some(rest) = rest[padding..].strip_prefix(&[0x00]) else { return false };
+
+    padding > 0 && rest.starts_with(&hash.digest_info(msg))
+}
+
+/// Bleichenbacher's e = 3 forgery: 00 01 FF 00 DigestInfo then garbage, with
+/// the garbage all FF so the cube root rounded down still lands inside the
+/// block. Needs a modulus long enough to have room for the garbage.
+pub fn forge(key: &PublicKey, hash: HashAlgorithm, msg: &[u8]) -> Result<Vec<u8>> {
+    let e = key.e.to_u64().filter(|e| *e <= 16).context("e is too big to forge for")?;
+
+    let mut block = vec![0x00, 0x01, 0xFF, 0x00];
+    block.extend(hash.digest_info(msg));
+    ensure!(block.len() < key.size(), "key too small for {hash:?}");
+    block.resize(key.size(), 0xFF);
+
+    let forged = BigUint::from_bytes_be(&block).nth_root(e as u32).to_bytes_be_padded(key.size());
+    ensure!(verify_sloppy(key, hash, msg, &forged), "no room for the garbage under this key");
+
+    Ok(forged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prime::PrimeGenerator;
+
+    #[test]
+    fn encoding() {
+        let encoded = encode(HashAlgorithm::Sha1, b"abc", 64).unwrap();
+        assert_eq!(encoded.len(), 64);
+        assert_eq!(&encoded[..3], &[0x00, 0x01, 0xFF]);
+        assert_eq!(encoded[64 - 36], 0x00);
+        assert_eq!(&encoded[64 - 20..], &Sha1::digest(b"abc"));
+
+        assert!(encode(HashAlgorithm::Sha256, b"abc", 61).is_err());
+        assert!(encode(HashAlgorithm::Sha256, b"abc", 62).is_ok());
+    }
+
+    #[test]
+    fn honest() {
+        let key = Keypair::generate_with(1024, 3, &mut PrimeGenerator::seeded(42));
+
+        for hash in [HashAlgorithm::Sha1, HashAlgorithm::Sha256] {
+            let signature = sign(&key, hash, b"hi mom").unwrap();
+            assert!(verify(&key.public, hash, b"hi mom", &signature));
+            assert!(verify_sloppy(&key.public, hash, b"hi mom", &signature));
+
+            assert!(!verify(&key.public, hash, b"hi dad", &signature));
+            assert!(!verify_sloppy(&key.public, hash, b"hi dad", &signature));
+        }
+    }
+
+    #[test]
+    fn example() {
+        let mut primes = PrimeGenerator::seeded(42);
+        let key = Keypair::generate_with(1024, 3, &mut primes);
+
+        let forged = forge(&key.public, HashAlgorithm::Sha1, b"hi mom").unwrap();
+        assert!(verify_sloppy(&key.public, HashAlgorithm::Sha1, b"hi mom", &forged));
+        assert!(!verify(&key.public, HashAlgorithm::Sha1, b"hi mom", &forged));
+
+        // SHA-256's DigestInfo is longer, which leaves too little garbage to
+        // hide the cube root's rounding in under 1024 bits
+        assert!(forge(&key.public, HashAlgorithm::Sha256, b"hi mom").is_err());
+
+        let key = Keypair::generate_with(1536, 3, &mut primes);
+        let forged = forge(&key.public, HashAlgorithm::Sha256, b"hi mom").unwrap();
+        assert!(verify_sloppy(&key.public, HashAlgorithm::Sha256, b"hi mom", &forged));
+        assert!(!verify(&key.public, HashAlgorithm::Sha256, b"hi mom", &forged));
+
+        // No such luck with a real exponent
+        let key = Keypair::generate_with(1024, 65537, &mut primes);
+        assert!(forge(&key.public, HashAlgorithm::Sha256, b"hi mom").is_err());
+    }
+}
@@ -0,0 +1,73 @@
+use aes::cipher::{generic_array::GenericArray, BlockEncrypt, KeyInit};
+
+/// Encrypt (or decrypt, it's the same thing) input with key with AES in CTR
+/// mode. The counter block is a 64 bit little endian nonce followed by a
+/// 64 bit little endian block count.
+pub fn aes_ctr(input: &[u8], key: &[u8], nonce: u64) -> Vec<u8> {
+    input
+        .iter()
+        .zip(ctr_keystream(key, nonce))
+        .map(|(x, y)| x ^ y)
+        .collect()
+}
+
+/// An endless AES-CTR keystream for key and nonce.
+pub fn ctr_keystream(key: &[u8], nonce: u64) -> impl Iterator<Item = u8> {
+    ctr_keystream_at(key, nonce, 0)
+}
+
+/// The AES-CTR keystream for key and nonce, starting `offset` bytes in.
+/// Only the blocks from `offset` onwards are generated.
+pub fn ctr_keystream_at(key: &[u8], nonce: u64, offset: usize) -> impl Iterator<Item = u8> {
+    let aes = aes::Aes128Enc::new_from_slice(key).unwrap();
+
+    ((offset / 16) as u64..)
+        .flat_map(move |counter| {
+            let mut block = [0u8; 16];
+            block[..8].copy_from_slice(&nonce.to_le_bytes());
+            block[8..].copy_from_slice(&counter.to_le_bytes());
+
+            let mut out = GenericArray::default();
+            aes.encrypt_block_b2b(GenericArray::from_slice(&block), &mut out);
+
+            out.to_vec()
+        })
+        .skip(offset % 16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::set_one::challenge_six::base64_decode;
+
+    #[test]
+    fn example() {
+        let input = base64_decode(
+            "L77na/nrFsKvynd6HzOoG7GHTLXsTVu9qvY/2syLXzhPweyyMTJULu/6/kXX0KSvoOLSFQ==");
+
+        let out = aes_ctr(&input, b"YELLOW SUBMARINE", 0);
+
+        assert_eq!(&out, b"Yo, VIP Let's kick it Ice, Ice, baby Ice, Ice, baby ");
+    }
+
+    #[test]
+    fn round_trip() {
+        let input = b"Test string! Test string! Test string! Test string!";
+        let key = b"YELLOW SUBMARINE";
+
+        let enc = aes_ctr(input, key, 0x1337);
+        assert_eq!(enc.len(), input.len());
+        assert_eq!(&aes_ctr(&enc, key, 0x1337), input);
+    }
+
+    #[test]
+    fn keystream_at_offset() {
+        let key = b"YELLOW SUBMARINE";
+        let keystream = ctr_keystream(key, 0).take(100).collect::<Vec<u8>>();
+
+        for offset in [0, 1, 15, 16, 17, 50] {
+            let at = ctr_keystream_at(key, 0, offset).take(100 - offset).collect::<Vec<u8>>();
+            assert_eq!(at, keystream[offset..]);
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use eyre::{ensure, ContextCompat, Result};
+use rand::seq::SliceRandom;
+
+use crate::set_one::challenge_six::base64_decode;
+use crate::set_two::{
+    challenge_eleven::{aes_cbc_enc, random_aes_key, Iv},
+    challenge_fifteen::strip_pkcs7,
+    challenge_ten::aes_cbc_dec,
+};
+
+const SECRET_INPUTS: &str = include_str!("../../vendor/challenge-seventeen.txt");
+
+/// The attacker-facing side of a CBC padding oracle. The key never leaves
+/// this struct; all you get back is whether a ciphertext decrypted to
+/// something with valid padding.
+pub struct PaddingOracle {
+    key: Vec<u8>,
+}
+
+impl PaddingOracle {
+    pub fn new() -> Self {
+        Self { key: random_aes_key() }
+    }
+
+    /// Encrypt a random one of the challenge's ten strings.
+    pub fn encrypt(&self) -> (Iv, Vec<u8>) {
+        let secrets = secrets();
+        let secret = secrets.choose(&mut rand::thread_rng()).unwrap();
+
+        aes_cbc_enc(secret, &self.key)
+    }
+
+    pub fn valid_padding(&self, iv: &[u8], ciphertext: &[u8]) -> bool {
+        aes_cbc_dec(ciphertext, &self.key, iv).is_ok_and(|plaintext| strip_pkcs7(&plaintext).is_ok())
+    }
+}
+
+impl Default for PaddingOracle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The ten plaintexts the oracle picks from
+pub fn secrets() -> Vec<Vec<u8>> {
+    SECRET_INPUTS
+        .lines()
+        .map(base64_decode)
+        .collect()
+}
+
+/// Recovers the plaintext of `ciphertext` given only a function that tells
+/// us whether an (iv, ciphertext) pair decrypts with valid padding.
+pub fn padding_oracle_attack<F>(iv: &[u8], ciphertext: &[u8], valid_padding: F) -> Result<Vec<u8>>
+where
+    F: Fn(&[u8], &[u8]) -> bool,
+{
+    ensure!(
+        !ciphertext.is_empty() && ciphertext.len().is_multiple_of(16),
+        "ciphertext isn't a whole number of blocks"
+    );
+    ensure!(iv.len() == 16, "iv isn't one block long");
+
+    let prevs = std::iter::once(iv).chain(ciphertext.chunks(16));
+
+    let mut plaintext = Vec::with_capacity(ciphertext.len());
+    for (block, prev) in ciphertext.chunks(16).zip(prevs) {
+        let intermediate = decrypt_block(block, &valid_padding)?;
+        plaintext.extend(intermediate.iter().zip(prev).map(|(x, y)| x ^ y));
+    }
+
+    strip_pkcs7(&plaintext)
+}
+
+/// Recovers the block cipher's raw output for `block` (i.e. before it's
+/// XORed with the previous ciphertext block) by feeding the oracle a forged
+/// IV one byte at a time.
+fn decrypt_block<F>(block: &[u8], valid_padding: &F) -> Result<[u8; 16]>
+where
+    F: Fn(&[u8], &[u8]) -> bool,
+{
+    let mut intermediate = [0u8; 16];
+
+    for pos in (0..16).rev() {
+        let pad = (16 - pos) as u8;
+
+        // Make every byte we already know decrypt to the padding value
+        let mut forged = [0u8; 16];
+        for idx in pos + 1..16 {
+            forged[idx] = intermediate[idx] ^ pad;
+        }
+
+        let byte = (0x00..=0xFF)
+            .find(|guess| {
+                forged[pos] = *guess;
+                if !valid_padding(&forged, block) {
+                    return false;
+                }
+
+                // For the last byte, a hit could also be \x02\x02 (or longer)
+                // if the byte before it happened to decrypt to \x02. Disturb
+                // that byte; real \x01 padding survives.
+                if pos == 15 {
+                    let mut check = forged;
+                    check[14] ^= 0xFF;
+                    return valid_padding(&check, block);
+                }
+
+                true
+            })
+            .context("no guess produced valid padding")?;
+
+        intermediate[pos] = byte ^ pad;
+    }
+
+    Ok(intermediate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::set_two::challenge_eleven::aes_ecb_enc;
+
+    #[test]
+    fn example() {
+        let oracle = PaddingOracle::new();
+        let secrets = secrets();
+
+        for _ in 0..20 {
+            let (iv, ciphertext) = oracle.encrypt();
+            let res = padding_oracle_attack(&iv, &ciphertext, |iv, ct| oracle.valid_padding(iv, ct))
+                .expect("recovered plaintext had bad padding");
+
+            assert!(secrets.contains(&res), "{:?}", String::from_utf8_lossy(&res));
+        }
+    }
+
+    #[test]
+    fn accidental_double_padding() {
+        let key = b"YELLOW SUBMARINE";
+
+        // Pick a ciphertext block whose raw decryption ends in \x02, so
+        // guessing the last byte against a zeroed IV can hit \x02\x02.
+        let mut intermediate = *b"0123456789ABCDEF";
+        intermediate[14] = 0x02;
+        let block = aes_ecb_enc(&intermediate, key)[..16].to_vec();
+
+        let plaintext = b"YELLOW SUBMARIN\x01";
+        let iv = plaintext
+            .iter()
+            .zip(intermediate)
+            .map(|(x, y)| x ^ y)
+            .collect::<Vec<u8>>();
+
+        let res = padding_oracle_attack(&iv, &block, |iv, ct| {
+            aes_cbc_dec(ct, key, iv).is_ok_and(|plaintext| strip_pkcs7(&plaintext).is_ok())
+        }).expect("recovered plaintext had bad padding");
+
+        assert_eq!(res, b"YELLOW SUBMARIN");
+    }
+
+    #[test]
+    fn bad_input() {
+        let oracle = PaddingOracle::new();
+        let (iv, ciphertext) = oracle.encrypt();
+
+        // Not whole blocks, or a short IV, is just bad padding
+        assert!(!oracle.valid_padding(&iv, &ciphertext[..15]));
+        assert!(!oracle.valid_padding(&iv, &[]));
+        assert!(!oracle.valid_padding(&iv[..15], &ciphertext));
+
+        assert!(padding_oracle_attack(&iv, &ciphertext[..15], |iv, ct| oracle.valid_padding(iv, ct)).is_err());
+        // An oracle that never says yes gives an error, not a panic
+        assert!(padding_oracle_attack(&iv, &ciphertext, |_, _| false).is_err());
+    }
+}
@@ -0,0 +1,158 @@
+use eyre::{ensure, ContextCompat, Result};
+
+use crate::set_one::{
+    challenge_six::transpose_ciphertext,
+    challenge_three::brute_force_single_byte_xor,
+};
+
+/// Recovers the keystream shared by `ciphertexts`, which were all encrypted
+/// in CTR mode under the same key and nonce.
+///
+/// Every ciphertext is truncated to the length of the shortest one, which
+/// turns the problem into repeating-key XOR with a known keysize.
+pub fn break_fixed_nonce_ctr(ciphertexts: &[Vec<u8>]) -> Result<Vec<u8>> {
+    let len = ciphertexts
+        .iter()
+        .map(|x| x.len())
+        .min()
+        .context("no ciphertexts")?;
+    ensure!(len > 0, "can't break an empty ciphertext");
+
+    let concatenated = ciphertexts
+        .iter()
+        .flat_map(|x| &x[..len])
+        .copied()
+        .collect::<Vec<u8>>();
+
+    transpose_ciphertext(&concatenated, len)
+        .iter()
+        .map(|column| best_key_byte(column))
+        .collect()
+}
+
+/// Like `break_fixed_nonce_ctr`, but keeps going past the shortest
+/// ciphertext, guessing each further keystream byte from however many
+/// ciphertexts are still long enough to cover it. Guesses get worse the
+/// fewer ciphertexts are left.
+pub fn break_fixed_nonce_ctr_ragged(ciphertexts: &[Vec<u8>]) -> Result<Vec<u8>> {
+    let mut keystream = break_fixed_nonce_ctr(ciphertexts)?;
+    let max_len = ciphertexts.iter().map(|x| x.len()).max().unwrap_or(0);
+
+    for idx in keystream.len()..max_len {
+        let column = ciphertexts
+            .iter()
+            .filter_map(|x| x.get(idx))
+            .copied()
+            .collect::<Vec<u8>>();
+
+        keystream.push(best_key_byte(&column)?);
+    }
+
+    Ok(keystream)
+}
+
+/// The chi2 scorer ignores everything but letters, so columns full of spaces
+/// and punctuation can fool it. Of its top guesses, take the one that
+/// produces the most letters and spaces.
+fn best_key_byte(column: &[u8]) -> Result<u8> {
+    let guesses = brute_force_single_byte_xor(column)?;
+    let (_, key, _) = guesses
+        .iter()
+        .rev()
+        .max_by_key(|(plaintext, _, _)| {
+            plaintext
+                .chars()
+                .filter(|x| x.is_ascii_alphabetic() || *x == ' ')
+                .count()
+        })
+        .context("couldn't guess key byte")?;
+
+    Ok(*key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::set_one::challenge_six::base64_decode;
+    use crate::set_three::challenge_eighteen::aes_ctr;
+    use crate::set_two::challenge_eleven::random_aes_key;
+
+    // Challenge 19's corpus, broken the challenge 20 way
+    const INPUTS: &str = include_str!("../../vendor/challenge-nineteen.txt");
+
+    fn encrypt_inputs() -> (Vec<Vec<u8>>, Vec<Vec<u8>>) {
+        let key = random_aes_key();
+        let plaintexts = INPUTS
+            .lines()
+            .map(base64_decode)
+            .collect::<Vec<Vec<u8>>>();
+        let ciphertexts = plaintexts
+            .iter()
+            .map(|x| aes_ctr(x, &key, 0))
+            .collect();
+
+        (plaintexts, ciphertexts)
+    }
+
+    fn decrypt(ciphertext: &[u8], keystream: &[u8]) -> Vec<u8> {
+        ciphertext
+            .iter()
+            .zip(keystream)
+            .map(|(x, y)| x ^ y)
+            .collect()
+    }
+
+    #[test]
+    fn truncated() {
+        let (plaintexts, ciphertexts) = encrypt_inputs();
+        let keystream = break_fixed_nonce_ctr(&ciphertexts).expect("couldn't break ctr");
+
+        let shortest = plaintexts.iter().map(|x| x.len()).min().unwrap();
+        assert_eq!(keystream.len(), shortest);
+
+        // The scorer only knows about lowercase letters, so it can't tell
+        // the case of the first character apart. Everything else must match.
+        for (plaintext, ciphertext) in plaintexts.iter().zip(&ciphertexts) {
+            let res = decrypt(ciphertext, &keystream);
+
+            assert!(
+                res.eq_ignore_ascii_case(&plaintext[..shortest]),
+                "{:?}", String::from_utf8_lossy(&res));
+        }
+    }
+
+    #[test]
+    fn ragged() {
+        let (plaintexts, ciphertexts) = encrypt_inputs();
+        let keystream = break_fixed_nonce_ctr_ragged(&ciphertexts).expect("couldn't break ctr");
+
+        let longest = plaintexts.iter().map(|x| x.len()).max().unwrap();
+        assert_eq!(keystream.len(), longest);
+
+        // The last few columns only have a ciphertext or two behind them,
+        // so just check the bulk of the text comes out right.
+        let (correct, total) = plaintexts
+            .iter()
+            .zip(&ciphertexts)
+            .flat_map(|(plaintext, ciphertext)| {
+                decrypt(ciphertext, &keystream)
+                    .into_iter()
+                    .zip(plaintext.clone())
+                    .map(|(x, y)| x.eq_ignore_ascii_case(&y))
+                    .collect::<Vec<bool>>()
+            })
+            .fold((0, 0), |(correct, total), x| (correct + x as usize, total + 1));
+
+        assert!(correct * 10 >= total * 9, "only {correct}/{total} bytes recovered");
+    }
+
+    #[test]
+    fn empty() {
+        let (_, mut ciphertexts) = encrypt_inputs();
+        ciphertexts.push(Vec::new());
+
+        assert!(break_fixed_nonce_ctr(&[]).is_err());
+        assert!(break_fixed_nonce_ctr(&ciphertexts).is_err());
+        assert!(break_fixed_nonce_ctr_ragged(&ciphertexts).is_err());
+    }
+}
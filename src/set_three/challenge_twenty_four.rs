@@ -0,0 +1,98 @@
+use rand::Rng;
+use rand_core::RngCore;
+
+use super::{challenge_twenty_one::Mt19937, challenge_twenty_two::Clock};
+
+/// How far back (in seconds) we look for a token's seed
+pub const TOKEN_MAX_AGE: u32 = 600;
+
+/// Encrypt (or decrypt) input with the keystream of an MT19937 seeded with
+/// `seed`. Each output makes up four bytes of keystream, little endian.
+pub fn mt_stream_cipher(input: &[u8], seed: u16) -> Vec<u8> {
+    let mut keystream = vec![0u8; input.len()];
+    Mt19937::new(seed as u32).fill_bytes(&mut keystream);
+
+    input
+        .iter()
+        .zip(keystream)
+        .map(|(x, y)| x ^ y)
+        .collect()
+}
+
+/// Prefix `known` with 5-40 random bytes and encrypt the lot.
+pub fn encrypt_with_random_prefix(known: &[u8], seed: u16) -> Vec<u8> {
+    let mut rng = rand::thread_rng();
+
+    let prefix_len = rng.gen_range(5..=40);
+    let mut plaintext = (0..prefix_len).map(|_| rng.gen()).collect::<Vec<u8>>();
+    plaintext.extend(known);
+
+    mt_stream_cipher(&plaintext, seed)
+}
+
+/// Recovers the 16 bit seed of a ciphertext whose plaintext we know ends
+/// with `known_suffix`. There's only 65536 of them, so try them all.
+pub fn recover_stream_seed(ciphertext: &[u8], known_suffix: &[u8]) -> Option<u16> {
+    (0..=u16::MAX).find(|seed| mt_stream_cipher(ciphertext, *seed).ends_with(known_suffix))
+}
+
+/// A "password reset token": 16 bytes from an MT19937 seeded with the time.
+pub fn password_reset_token(clock: &impl Clock) -> Vec<u8> {
+    let mut token = vec![0u8; 16];
+    Mt19937::new(clock.now()).fill_bytes(&mut token);
+
+    token
+}
+
+/// Whether `token` came out of an MT19937 seeded with the time at some
+/// point in the last `TOKEN_MAX_AGE` seconds.
+pub fn is_time_seeded_token(token: &[u8], clock: &impl Clock) -> bool {
+    let now = clock.now();
+
+    (now.saturating_sub(TOKEN_MAX_AGE)..=now).any(|seed| {
+        let mut guess = vec![0u8; token.len()];
+        Mt19937::new(seed).fill_bytes(&mut guess);
+
+        guess == token
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::set_three::challenge_twenty_two::{FakeClock, SystemClock};
+
+    #[test]
+    fn round_trip() {
+        let input = b"Test string! Test string! Test string! Test string!";
+
+        let enc = mt_stream_cipher(input, 0x1337);
+        assert_ne!(&enc, input);
+        assert_eq!(&mt_stream_cipher(&enc, 0x1337), input);
+    }
+
+    #[test]
+    fn example() {
+        let known = b"AAAAAAAAAAAAAA";
+        let seed = rand::random();
+
+        let ciphertext = encrypt_with_random_prefix(known, seed);
+
+        assert_eq!(recover_stream_seed(&ciphertext, known), Some(seed));
+    }
+
+    #[test]
+    fn reset_token() {
+        let clock = FakeClock::new(SystemClock.now());
+
+        let token = password_reset_token(&clock);
+        clock.sleep(30);
+        assert!(is_time_seeded_token(&token, &clock));
+
+        clock.sleep(TOKEN_MAX_AGE);
+        assert!(!is_time_seeded_token(&token, &clock));
+
+        let random_token = rand::random::<[u8; 16]>();
+        assert!(!is_time_seeded_token(&random_token, &clock));
+    }
+}
@@ -0,0 +1,326 @@
+//! The Mersenne Twister, in its 32 and 64 bit flavours, straight from the
+//! reference implementations (mt19937ar.c and mt19937-64.c).
+use rand_core::{impls, RngCore, SeedableRng};
+
+pub const N: usize = 624;
+const M: usize = 397;
+const MATRIX_A: u32 = 0x9908_B0DF;
+const UPPER_MASK: u32 = 0x8000_0000;
+const LOWER_MASK: u32 = 0x7FFF_FFFF;
+
+pub const NN: usize = 312;
+const MM: usize = 156;
+const MATRIX_A_64: u64 = 0xB502_6F5A_A966_19E9;
+const UPPER_MASK_64: u64 = 0xFFFF_FFFF_8000_0000;
+const LOWER_MASK_64: u64 = 0x7FFF_FFFF;
+
+/// The reference implementation's default seed
+pub const DEFAULT_SEED: u32 = 5489;
+
+#[derive(Clone)]
+pub struct Mt19937 {
+    state: [u32; N],
+    index: usize,
+}
+
+impl Mt19937 {
+    pub fn new(seed: u32) -> Self {
+        let mut state = [0u32; N];
+        state[0] = seed;
+        for i in 1..N {
+            state[i] = 1_812_433_253u32
+                .wrapping_mul(state[i - 1] ^ (state[i - 1] >> 30))
+                .wrapping_add(i as u32);
+        }
+
+        Self { state, index: N }
+    }
+
+    /// Picks up from raw (untempered) internal state. The state is twisted
+    /// before the first output, as if it had just been exhausted.
+    pub fn from_state(state: [u32; N]) -> Self {
+        Self { state, index: N }
+    }
+
+    /// Seeds the generator from an array, like the reference `init_by_array`.
+    /// The key can't be empty; the reference reads its first word regardless.
+    pub fn from_array(key: &[u32]) -> Self {
+        assert!(!key.is_empty(), "can't seed from an empty key");
+        let mut mt = Self::new(19_650_218);
+        let state = &mut mt.state;
+
+        let (mut i, mut j) = (1, 0);
+        for _ in 0..N.max(key.len()) {
+            state[i] = (state[i] ^ (state[i - 1] ^ (state[i - 1] >> 30)).wrapping_mul(1_664_525))
+                .wrapping_add(key[j])
+                .wrapping_add(j as u32);
+            i += 1;
+            j += 1;
+            if i >= N {
+                state[0] = state[N - 1];
+                i = 1;
+            }
+            if j >= key.len() {
+                j = 0;
+            }
+        }
+        for _ in 0..N - 1 {
+            state[i] = (state[i] ^ (state[i - 1] ^ (state[i - 1] >> 30)).wrapping_mul(1_566_083_941))
+                .wrapping_sub(i as u32);
+            i += 1;
+            if i >= N {
+                state[0] = state[N - 1];
+                i = 1;
+            }
+        }
+        state[0] = 0x8000_0000;
+
+        mt
+    }
+
+    fn twist(&mut self) {
+        for i in 0..N {
+            let y = (self.state[i] & UPPER_MASK) | (self.state[(i + 1) % N] & LOWER_MASK);
+            let mag = if y & 1 == 0 { 0 } else { MATRIX_A };
+
+            self.state[i] = self.state[(i + M) % N] ^ (y >> 1) ^ mag;
+        }
+
+        self.index = 0;
+    }
+}
+
+/// The output tempering applied to each word of MT19937 state.
+pub fn temper(mut y: u32) -> u32 {
+    y ^= y >> 11;
+    y ^= (y << 7) & 0x9D2C_5680;
+    y ^= (y << 15) & 0xEFC6_0000;
+    y ^ (y >> 18)
+}
+
+impl RngCore for Mt19937 {
+    fn next_u32(&mut self) -> u32 {
+        if self.index >= N {
+            self.twist();
+        }
+
+        let y = self.state[self.index];
+        self.index += 1;
+
+        temper(y)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        impls::next_u64_via_u32(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        impls::fill_bytes_via_next(self, dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl SeedableRng for Mt19937 {
+    type Seed = [u8; 4];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self::new(u32::from_le_bytes(seed))
+    }
+
+    // The default would scramble the seed first, which stops the output
+    // matching the reference implementation.
+    fn seed_from_u64(state: u64) -> Self {
+        Self::new(state as u32)
+    }
+}
+
+#[derive(Clone)]
+pub struct Mt19937_64 {
+    state: [u64; NN],
+    index: usize,
+}
+
+impl Mt19937_64 {
+    pub fn new(seed: u64) -> Self {
+        let mut state = [0u64; NN];
+        state[0] = seed;
+        for i in 1..NN {
+            state[i] = 6_364_136_223_846_793_005u64
+                .wrapping_mul(state[i - 1] ^ (state[i - 1] >> 62))
+                .wrapping_add(i as u64);
+        }
+
+        Self { state, index: NN }
+    }
+
+    /// Picks up from raw (untempered) internal state. The state is twisted
+    /// before the first output, as if it had just been exhausted.
+    pub fn from_state(state: [u64; NN]) -> Self {
+        Self { state, index: NN }
+    }
+
+    /// Seeds the generator from an array, like the reference `init_by_array64`.
+    /// The key can't be empty; the reference reads its first word regardless.
+    pub fn from_array(key: &[u64]) -> Self {
+        assert!(!key.is_empty(), "can't seed from an empty key");
+        let mut mt = Self::new(19_650_218);
+        let state = &mut mt.state;
+
+        let (mut i, mut j) = (1, 0);
+        for _ in 0..NN.max(key.len()) {
+            state[i] = (state[i]
+                ^ (state[i - 1] ^ (state[i - 1] >> 62)).wrapping_mul(3_935_559_000_370_003_845))
+                .wrapping_add(key[j])
+                .wrapping_add(j as u64);
+            i += 1;
+            j += 1;
+            if i >= NN {
+                state[0] = state[NN - 1];
+                i = 1;
+            }
+            if j >= key.len() {
+                j = 0;
+            }
+        }
+        for _ in 0..NN - 1 {
+            state[i] = (state[i]
+                ^ (state[i - 1] ^ (state[i - 1] >> 62)).wrapping_mul(2_862_933_555_777_941_757))
+                .wrapping_sub(i as u64);
+            i += 1;
+            if i >= NN {
+                state[0] = state[NN - 1];
+                i = 1;
+            }
+        }
+        state[0] = 1 << 63;
+
+        mt
+    }
+
+    fn twist(&mut self) {
+        for i in 0..NN {
+            let y = (self.state[i] & UPPER_MASK_64) | (self.state[(i + 1) % NN] & LOWER_MASK_64);
+            let mag = if y & 1 == 0 { 0 } else { MATRIX_A_64 };
+
+            self.state[i] = self.state[(i + MM) % NN] ^ (y >> 1) ^ mag;
+        }
+
+        self.index = 0;
+    }
+}
+
+/// The output tempering applied to each word of MT19937-64 state.
+pub fn temper_64(mut y: u64) -> u64 {
+    y ^= (y >> 29) & 0x5555_5555_5555_5555;
+    y ^= (y << 17) & 0x71D6_7FFF_EDA6_0000;
+    y ^= (y << 37) & 0xFFF7_EEE0_0000_0000;
+    y ^ (y >> 43)
+}
+
+impl RngCore for Mt19937_64 {
+    fn next_u32(&mut self) -> u32 {
+        self.next_u64() as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        if self.index >= NN {
+            self.twist();
+        }
+
+        let y = self.state[self.index];
+        self.index += 1;
+
+        temper_64(y)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        impls::fill_bytes_via_next(self, dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl SeedableRng for Mt19937_64 {
+    type Seed = [u8; 8];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self::new(u64::from_le_bytes(seed))
+    }
+
+    fn seed_from_u64(state: u64) -> Self {
+        Self::new(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+
+    #[test]
+    fn default_seed() {
+        let mut mt = Mt19937::new(DEFAULT_SEED);
+        assert_eq!(mt.next_u32(), 3_499_211_612);
+
+        // The 10000th output, as given in the C++ standard
+        let mut mt = Mt19937::new(DEFAULT_SEED);
+        let last = (0..10_000).map(|_| mt.next_u32()).last();
+        assert_eq!(last, Some(4_123_659_995));
+    }
+
+    #[test]
+    fn init_by_array() {
+        // First outputs from mt19937ar.out
+        let mut mt = Mt19937::from_array(&[0x123, 0x234, 0x345, 0x456]);
+        let out = (0..5).map(|_| mt.next_u32()).collect::<Vec<u32>>();
+
+        assert_eq!(out, [1_067_595_299, 955_945_823, 477_289_528, 4_107_218_783, 4_228_976_476]);
+    }
+
+    #[test]
+    fn default_seed_64() {
+        let mut mt = Mt19937_64::new(DEFAULT_SEED as u64);
+        assert_eq!(mt.next_u64(), 14_514_284_786_278_117_030);
+
+        // The 10000th output, as given in the C++ standard
+        let mut mt = Mt19937_64::new(DEFAULT_SEED as u64);
+        let last = (0..10_000).map(|_| mt.next_u64()).last();
+        assert_eq!(last, Some(9_981_545_732_273_789_042));
+    }
+
+    #[test]
+    fn init_by_array_64() {
+        // First outputs from mt19937-64.out.txt
+        let mut mt = Mt19937_64::from_array(&[0x12345, 0x23456, 0x34567, 0x45678]);
+        let out = (0..5).map(|_| mt.next_u64()).collect::<Vec<u64>>();
+
+        assert_eq!(out, [
+            7_266_447_313_870_364_031,
+            4_946_485_549_665_804_864,
+            16_945_909_448_695_747_420,
+            16_394_063_075_524_226_720,
+            4_873_882_236_456_199_058,
+        ]);
+    }
+
+    #[test]
+    fn as_rand_rng() {
+        // Usable anywhere StdRng is, e.g. generating an AES key
+        let mut rng = Mt19937::from_seed(DEFAULT_SEED.to_le_bytes());
+        let mut key = [0u8; 16];
+        rng.fill_bytes(&mut key);
+        assert_eq!(&key[..4], &3_499_211_612u32.to_le_bytes());
+
+        let mut rng = Mt19937_64::seed_from_u64(DEFAULT_SEED as u64);
+        let n: u64 = rng.gen();
+        assert_eq!(n, 14_514_284_786_278_117_030);
+    }
+}
@@ -0,0 +1,89 @@
+use super::challenge_twenty_one::{Mt19937, Mt19937_64, N, NN};
+
+/// Inverts MT19937's output tempering, giving back the raw state word.
+pub fn untemper(y: u32) -> u32 {
+    let y = y as u64;
+    let y = undo_right_shift_xor(y, 18, 0xFFFF_FFFF);
+    let y = undo_left_shift_xor(y, 15, 0xEFC6_0000);
+    let y = undo_left_shift_xor(y, 7, 0x9D2C_5680);
+    let y = undo_right_shift_xor(y, 11, 0xFFFF_FFFF);
+
+    y as u32
+}
+
+/// Inverts MT19937-64's output tempering, giving back the raw state word.
+pub fn untemper_64(y: u64) -> u64 {
+    let y = undo_right_shift_xor(y, 43, u64::MAX);
+    let y = undo_left_shift_xor(y, 37, 0xFFF7_EEE0_0000_0000);
+    let y = undo_left_shift_xor(y, 17, 0x71D6_7FFF_EDA6_0000);
+
+    undo_right_shift_xor(y, 29, 0x5555_5555_5555_5555)
+}
+
+/// Rebuilds an MT19937 from 624 consecutive outputs, starting right after a
+/// twist. The clone's next output is the original's next output.
+pub fn clone_from_outputs(outputs: &[u32; N]) -> Mt19937 {
+    Mt19937::from_state(outputs.map(untemper))
+}
+
+/// Rebuilds an MT19937-64 from 312 consecutive outputs, starting right after
+/// a twist.
+pub fn clone_from_outputs_64(outputs: &[u64; NN]) -> Mt19937_64 {
+    Mt19937_64::from_state(outputs.map(untemper_64))
+}
+
+// Inverts y ^= (y >> shift) & mask. Each pass fixes another `shift` bits,
+// starting from the top ones which were never touched.
+fn undo_right_shift_xor(y: u64, shift: u32, mask: u64) -> u64 {
+    (0..64 / shift).fold(y, |x, _| y ^ ((x >> shift) & mask))
+}
+
+// Inverts y ^= (y << shift) & mask, fixing bits from the bottom up.
+fn undo_left_shift_xor(y: u64, shift: u32, mask: u64) -> u64 {
+    (0..64 / shift).fold(y, |x, _| y ^ ((x << shift) & mask))
+}
+
+#[cfg(test)]
+mod tests {
+    use rand_core::RngCore;
+
+    use super::*;
+    use crate::set_three::challenge_twenty_one::{temper, temper_64};
+
+    #[test]
+    fn test_untemper() {
+        for _ in 0..1000 {
+            let y = rand::random();
+            assert_eq!(untemper(temper(y)), y);
+
+            let y = rand::random();
+            assert_eq!(untemper_64(temper_64(y)), y);
+        }
+    }
+
+    #[test]
+    fn example() {
+        let mut mt = Mt19937::new(rand::random());
+
+        let mut outputs = [0u32; N];
+        outputs.iter_mut().for_each(|x| *x = mt.next_u32());
+        let mut clone = clone_from_outputs(&outputs);
+
+        for _ in 0..10_000 {
+            assert_eq!(clone.next_u32(), mt.next_u32());
+        }
+    }
+
+    #[test]
+    fn example_64() {
+        let mut mt = Mt19937_64::new(rand::random());
+
+        let mut outputs = [0u64; NN];
+        outputs.iter_mut().for_each(|x| *x = mt.next_u64());
+        let mut clone = clone_from_outputs_64(&outputs);
+
+        for _ in 0..10_000 {
+            assert_eq!(clone.next_u64(), mt.next_u64());
+        }
+    }
+}
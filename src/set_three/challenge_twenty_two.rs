@@ -0,0 +1,97 @@
+use std::{cell::Cell, ops::RangeInclusive, time::{Duration, SystemTime, UNIX_EPOCH}};
+use rand::Rng;
+use rand_core::RngCore;
+
+use super::challenge_twenty_one::Mt19937;
+
+/// Somewhere to get the time from, and a way to pass it.
+pub trait Clock {
+    /// Seconds since the unix epoch
+    fn now(&self) -> u32;
+    fn sleep(&self, secs: u32);
+}
+
+/// The real thing.
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> u32 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system clock is before the epoch")
+            .as_secs() as u32
+    }
+
+    fn sleep(&self, secs: u32) {
+        std::thread::sleep(Duration::from_secs(secs as u64));
+    }
+}
+
+/// A clock that only moves when you sleep on it, so tests don't have to
+/// actually wait.
+pub struct FakeClock {
+    now: Cell<u32>,
+}
+
+impl FakeClock {
+    pub fn new(now: u32) -> Self {
+        Self { now: Cell::new(now) }
+    }
+}
+
+impl Clock for FakeClock {
+    fn now(&self) -> u32 {
+        self.now.get()
+    }
+
+    fn sleep(&self, secs: u32) {
+        self.now.set(self.now.get() + secs);
+    }
+}
+
+/// The challenge's scenario: wait a random 40-1000 seconds, seed an MT19937
+/// with the current time, wait some more, and return the first output.
+pub fn timestamp_seeded_output(clock: &impl Clock) -> u32 {
+    let mut rng = rand::thread_rng();
+
+    clock.sleep(rng.gen_range(40..=1000));
+    let mut mt = Mt19937::new(clock.now());
+    clock.sleep(rng.gen_range(40..=1000));
+
+    mt.next_u32()
+}
+
+/// Finds the seed in `window` whose first MT19937 output is `output`.
+pub fn recover_seed(output: u32, window: RangeInclusive<u32>) -> Option<u32> {
+    window
+        .into_iter()
+        .find(|seed| Mt19937::new(*seed).next_u32() == output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example() {
+        let clock = FakeClock::new(SystemClock.now());
+        let start = clock.now();
+
+        let output = timestamp_seeded_output(&clock);
+
+        // Anything between when we started and now could've been the seed
+        let seed = recover_seed(output, start..=clock.now())
+            .expect("couldn't find seed");
+
+        assert_eq!(Mt19937::new(seed).next_u32(), output);
+        assert!((start + 40..=start + 1000).contains(&seed));
+    }
+
+    #[test]
+    fn fake_clock() {
+        let clock = FakeClock::new(1000);
+        clock.sleep(234);
+
+        assert_eq!(clock.now(), 1234);
+    }
+}
@@ -18,13 +18,20 @@ pub fn random_aes_key() -> Vec<u8> {
 
 /// Encrypt input with key with AES in CBC mode
 pub fn aes_cbc_enc(input: &[u8], key: &[u8]) -> (Iv, Vec<u8>) {
-    let input = pad_pkcs7(input, 16);
     let iv = random_aes_key();
+    let out = aes_cbc_enc_with_iv(input, key, &iv);
+
+    (iv, out)
+}
+
+/// Encrypt input with key with AES in CBC mode, using a caller-chosen iv
+pub fn aes_cbc_enc_with_iv(input: &[u8], key: &[u8], iv: &[u8]) -> Vec<u8> {
+    let input = pad_pkcs7(input, 16);
 
-    let mut out = vec![];
+    let mut out: Vec<Vec<u8>> = vec![];
     for (idx, block) in input.chunks(16).enumerate() {
         let prev = if idx == 0 {
-            &iv
+            iv
         } else {
             &out[idx - 1]
         };
@@ -42,7 +49,7 @@ pub fn aes_cbc_enc(input: &[u8], key: &[u8]) -> (Iv, Vec<u8>) {
         out.push(out_block.to_vec());
     }
 
-    (iv, out.into_iter().flatten().collect())
+    out.into_iter().flatten().collect()
 }
 
 /// Encrypt input with key with AES in ECB mode
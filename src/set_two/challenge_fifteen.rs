@@ -0,0 +1,33 @@
+use eyre::{ensure, ContextCompat, Result};
+
+/// Strips PKCS#7 padding from `input`, failing if the padding is invalid.
+pub fn strip_pkcs7(input: &[u8]) -> Result<Vec<u8>> {
+    let padding_len = *input.last().context("input is empty")? as usize;
+
+    ensure!(padding_len != 0 && padding_len <= input.len(), "invalid padding length");
+    ensure!(
+        input[input.len() - padding_len..].iter().all(|x| *x as usize == padding_len),
+        "invalid padding bytes");
+
+    Ok(input[..input.len() - padding_len].to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example() {
+        let r = strip_pkcs7(b"ICE ICE BABY\x04\x04\x04\x04").expect("valid padding rejected");
+
+        assert_eq!(r, b"ICE ICE BABY");
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(strip_pkcs7(b"ICE ICE BABY\x05\x05\x05\x05").is_err());
+        assert!(strip_pkcs7(b"ICE ICE BABY\x01\x02\x03\x04").is_err());
+        assert!(strip_pkcs7(b"ICE ICE BABY\x00").is_err());
+        assert!(strip_pkcs7(b"").is_err());
+    }
+}
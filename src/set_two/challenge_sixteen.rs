@@ -0,0 +1,137 @@
+use eyre::{ensure, Result};
+use crate::set_three::challenge_eighteen::aes_ctr;
+
+use super::{
+    challenge_eleven::{aes_cbc_enc, random_aes_key},
+    challenge_fifteen::strip_pkcs7,
+    challenge_ten::aes_cbc_dec,
+};
+
+pub const PREFIX: &[u8] = b"comment1=cooking%20MCs;userdata=";
+pub const SUFFIX: &[u8] = b";comment2=%20like%20a%20pound%20of%20bacon";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CipherMode {
+    Cbc,
+    Ctr,
+}
+
+/// Wraps user data in a comment string and encrypts it. The only thing it
+/// tells you about a ciphertext is whether it grants admin.
+pub struct CommentService {
+    mode: CipherMode,
+    key: Vec<u8>,
+    nonce: u64,
+}
+
+impl CommentService {
+    pub fn new(mode: CipherMode) -> Self {
+        Self {
+            mode,
+            key: random_aes_key(),
+            nonce: rand::random(),
+        }
+    }
+
+    pub fn mode(&self) -> CipherMode {
+        self.mode
+    }
+
+    /// Quotes out ';' and '=' in `userdata`, wraps it, and encrypts it.
+    /// In CBC mode the IV is prepended to the ciphertext.
+    pub fn encrypt(&self, userdata: &[u8]) -> Vec<u8> {
+        let mut plaintext = PREFIX.to_vec();
+        for byte in userdata {
+            match byte {
+                b';' => plaintext.extend(b"%3B"),
+                b'=' => plaintext.extend(b"%3D"),
+                _ => plaintext.push(*byte),
+            }
+        }
+        plaintext.extend(SUFFIX);
+
+        match self.mode {
+            CipherMode::Cbc => {
+                let (mut iv, ciphertext) = aes_cbc_enc(&plaintext, &self.key);
+                iv.extend(ciphertext);
+                iv
+            }
+            CipherMode::Ctr => aes_ctr(&plaintext, &self.key, self.nonce),
+        }
+    }
+
+    /// Decrypts and looks for an `admin=true` field.
+    pub fn is_admin(&self, ciphertext: &[u8]) -> bool {
+        let Ok(plaintext) = self.decrypt(ciphertext) else {
+            return false;
+        };
+
+        plaintext
+            .split(|x| *x == b';')
+            .any(|field| field == b"admin=true")
+    }
+
+    pub(crate) fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        match self.mode {
+            CipherMode::Cbc => {
+                ensure!(
+                    ciphertext.len() >= 32 && ciphertext.len().is_multiple_of(16),
+                    "ciphertext isn't an IV and whole blocks"
+                );
+                let (iv, ciphertext) = ciphertext.split_at(16);
+
+                strip_pkcs7(&aes_cbc_dec(ciphertext, &self.key, iv)?)
+            }
+            CipherMode::Ctr => Ok(aes_ctr(ciphertext, &self.key, self.nonce)),
+        }
+    }
+}
+
+/// Gets a CBC comment service to grant admin by flipping bits in the block
+/// before our input, sacrificing a block of filler to do it.
+pub fn cbc_bitflip(service: &CommentService) -> Vec<u8> {
+    // ':' and '<' are one bit away from ';' and '='. The prefix is exactly
+    // two blocks, so the filler is the third block and the payload the fourth.
+    let mut userdata = vec![b'A'; 16];
+    userdata.extend(b":admin<true");
+    let mut ciphertext = service.encrypt(&userdata);
+
+    // Skip the IV, the prefix, then land back in the filler block.
+    let payload = 16 + PREFIX.len() + 16;
+    ciphertext[payload - 16] ^= 1;
+    ciphertext[payload - 16 + 6] ^= 1;
+
+    ciphertext
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quoting() {
+        let service = CommentService::new(CipherMode::Cbc);
+        let ciphertext = service.encrypt(b";admin=true;");
+
+        assert!(!service.is_admin(&ciphertext));
+        assert_eq!(
+            service.decrypt(&ciphertext).unwrap(),
+            b"comment1=cooking%20MCs;userdata=%3Badmin%3Dtrue%3B;comment2=%20like%20a%20pound%20of%20bacon");
+    }
+
+    #[test]
+    fn malformed() {
+        let service = CommentService::new(CipherMode::Cbc);
+
+        assert!(!service.is_admin(&[0u8; 16]));
+        assert!(!service.is_admin(&[0u8; 40]));
+        assert!(service.decrypt(&[0u8; 40]).is_err());
+    }
+
+    #[test]
+    fn example() {
+        let service = CommentService::new(CipherMode::Cbc);
+
+        assert!(service.is_admin(&cbc_bitflip(&service)));
+    }
+}
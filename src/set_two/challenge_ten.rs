@@ -1,5 +1,5 @@
 use aes::cipher::{generic_array::GenericArray, BlockDecrypt, KeyInit};
-use eyre::Result;
+use eyre::{ensure, Result};
 
 pub fn decode_aes_cbc(
     ciphertext: &[u8],
@@ -41,10 +41,41 @@ pub fn decode_aes_cbc(
     Ok(result)
 }
 
+/// Decrypt ciphertext with key and iv with AES in CBC mode.
+/// Padding is left in place for the caller to check.
+pub fn aes_cbc_dec(ciphertext: &[u8], key: &[u8], iv: &[u8]) -> Result<Vec<u8>> {
+    ensure!(
+        !ciphertext.is_empty() && ciphertext.len().is_multiple_of(16),
+        "ciphertext isn't a whole number of blocks"
+    );
+    ensure!(iv.len() == 16, "iv isn't one block long");
+
+    let aes = aes::Aes128Dec::new(key.into());
+
+    let prevs = std::iter::once(iv).chain(ciphertext.chunks(16));
+    let plaintext = ciphertext
+        .chunks(16)
+        .zip(prevs)
+        .flat_map(|(curr, prev)| {
+            let mut dec = GenericArray::clone_from_slice(curr);
+            aes.decrypt_block(&mut dec);
+
+            dec
+                .iter()
+                .zip(prev.iter())
+                .map(|(x, y)| x ^ y)
+                .collect::<Vec<u8>>()
+        })
+        .collect();
+
+    Ok(plaintext)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::set_one::challenge_six::base64_decode;
+    use crate::set_two::{challenge_eleven::aes_cbc_enc, challenge_nine::pad_pkcs7};
 
     const ENC_INPUT: &str = include_str!("../../vendor/challenge-ten.txt");
     const ENC_OUTPUT: &str = include_str!("../../vendor/challenge-ten-dec.txt");
@@ -60,4 +91,19 @@ mod tests {
         
         assert!(out == ENC_OUTPUT);
     }
+
+    #[test]
+    fn test_aes_cbc_dec() {
+        let input = b"Test string! Test string! Test string! Test string!";
+        let key = b"YELLOW SUBMARINE";
+
+        let (iv, enc) = aes_cbc_enc(input, key);
+        let dec = aes_cbc_dec(&enc, key, &iv).unwrap();
+
+        assert_eq!(dec, pad_pkcs7(input, 16));
+
+        assert!(aes_cbc_dec(&enc[..enc.len() - 1], key, &iv).is_err());
+        assert!(aes_cbc_dec(&[], key, &iv).is_err());
+        assert!(aes_cbc_dec(&enc, key, &iv[..15]).is_err());
+    }
 }